@@ -1,21 +1,86 @@
 use token::Token;
+use span::Span;
 
 #[derive(Debug, PartialEq, Clone)]
 pub enum AST {
-    Program {children: Vec<Box<AST>>},
-    Bloc {children: Vec<Box<AST>>},
-    IfStatement {if_compound: (Box<AST>, Box<AST>), else_if_compounds: Vec<(Box<AST>, Box<AST>)>, else_compound: Box<AST>},
-    WhileStatement {condition: Box<AST>, bloc: Box<AST>},
-    FunctionDeclaration {identifier: Token, parameters: Vec<AST>, body: Box<AST>},
-    Parameter {parameter: Box<AST>},
-    ReturnStatement {expression: Box<AST>},
-    Assignment {left: Box<AST>, right: Box<AST>},
-    BinaryOperation {left: Box<AST>, op: Token, right: Box<AST>},
-    UnaryOperation {op: Token, right: Box<AST>},
-    IntNumber {token: Token},
-    FloatNumber {token: Token},
-    Boolean {token: Token},
-    FunctionCall {identifier: Token, arguments: Vec<AST>},
-    Variable {id: Token},
+    Program {children: Vec<Box<AST>>, span: Span},
+    Bloc {children: Vec<Box<AST>>, span: Span},
+    IfStatement {if_compound: (Box<AST>, Box<AST>), else_if_compounds: Vec<(Box<AST>, Box<AST>)>, else_compound: Box<AST>, span: Span},
+    WhileStatement {condition: Box<AST>, bloc: Box<AST>, span: Span},
+    ForStatement {variable: Box<AST>, iterable: Box<AST>, bloc: Box<AST>, span: Span},
+    FunctionDeclaration {identifier: Token, parameters: Vec<AST>, body: Box<AST>, span: Span},
+    Parameter {parameter: Box<AST>, span: Span},
+    ReturnStatement {expression: Box<AST>, span: Span},
+    BreakStatement {span: Span},
+    ContinueStatement {span: Span},
+    GlobalStatement {names: Vec<Token>, span: Span},
+    NonlocalStatement {names: Vec<Token>, span: Span},
+    Assignment {left: Box<AST>, right: Box<AST>, span: Span},
+    AugmentedAssignment {left: Box<AST>, op: Token, right: Box<AST>, span: Span},
+    Conditional {condition: Box<AST>, then_branch: Box<AST>, else_branch: Box<AST>, span: Span},
+    BinaryOperation {left: Box<AST>, op: Token, right: Box<AST>, span: Span},
+    Comparison {operands: Vec<AST>, operators: Vec<Token>, span: Span},
+    UnaryOperation {op: Token, right: Box<AST>, span: Span},
+    IntNumber {token: Token, span: Span},
+    FloatNumber {token: Token, span: Span},
+    String {token: Token, span: Span},
+    Boolean {token: Token, span: Span},
+    FunctionCall {callee: Box<AST>, arguments: Vec<AST>, span: Span},
+    Lambda {parameters: Vec<AST>, body: Box<AST>, span: Span},
+    List {elements: Vec<AST>, span: Span},
+    Tuple {elements: Vec<AST>, span: Span},
+    /// Element of an assignment target taking the remaining values, as in `first, *rest = xs`.
+    Starred {value: Box<AST>, span: Span},
+    Dict {entries: Vec<(AST, AST)>, span: Span},
+    Attribute {value: Box<AST>, name: Token, span: Span},
+    Index {value: Box<AST>, index: Box<AST>, span: Span},
+    /// Index of a slice, its omitted bounds are `Empty`.
+    Slice {start: Box<AST>, stop: Box<AST>, step: Box<AST>, span: Span},
+    Variable {id: Token, span: Span},
+    Error {span: Span},
     Empty,
 }
+
+impl AST {
+
+    /// Location of the node in the source code.
+    /// `Empty` nodes have no source, their span is the default one.
+    pub fn span(&self) -> Span {
+        match self {
+            AST::Program {span, ..}
+            | AST::Bloc {span, ..}
+            | AST::IfStatement {span, ..}
+            | AST::WhileStatement {span, ..}
+            | AST::ForStatement {span, ..}
+            | AST::FunctionDeclaration {span, ..}
+            | AST::Parameter {span, ..}
+            | AST::ReturnStatement {span, ..}
+            | AST::BreakStatement {span}
+            | AST::ContinueStatement {span}
+            | AST::GlobalStatement {span, ..}
+            | AST::NonlocalStatement {span, ..}
+            | AST::Assignment {span, ..}
+            | AST::AugmentedAssignment {span, ..}
+            | AST::Conditional {span, ..}
+            | AST::BinaryOperation {span, ..}
+            | AST::Comparison {span, ..}
+            | AST::UnaryOperation {span, ..}
+            | AST::IntNumber {span, ..}
+            | AST::FloatNumber {span, ..}
+            | AST::String {span, ..}
+            | AST::Boolean {span, ..}
+            | AST::FunctionCall {span, ..}
+            | AST::Lambda {span, ..}
+            | AST::List {span, ..}
+            | AST::Tuple {span, ..}
+            | AST::Starred {span, ..}
+            | AST::Dict {span, ..}
+            | AST::Attribute {span, ..}
+            | AST::Index {span, ..}
+            | AST::Slice {span, ..}
+            | AST::Variable {span, ..}
+            | AST::Error {span} => *span,
+            AST::Empty => Span::default(),
+        }
+    }
+}
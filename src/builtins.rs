@@ -1,14 +1,97 @@
 use std::collections::HashMap;
+use unicode_segmentation::UnicodeSegmentation;
 use value::Value;
+use error::BjornError;
 
-pub type Callback = fn(Value) -> Value;
+pub type Callback = fn(Vec<Value>) -> Result<Value, BjornError>;
 
-fn _print(_arg: Value) -> Value {
-    println!("{}", _arg);
-    Value::None
+pub fn arity(name: &str, arguments: &[Value], min: usize, max: usize) -> Result<(), BjornError> {
+    if arguments.len() >= min && arguments.len() <= max {
+        return Ok(())
+    }
+    let expected = if min == max {
+        format!("exactly {}", min)
+    } else {
+        format!("from {} to {}", min, max)
+    };
+    Err(BjornError::ArityError(format!(
+        "`{}` takes {} argument(s) but {} were given", name, expected, arguments.len()
+    ).into()))
+}
+
+fn _print(_args: Vec<Value>) -> Result<Value, BjornError> {
+    arity("print", &_args, 1, 1)?;
+    println!("{}", _args[0]);
+    Ok(Value::None)
+}
+
+fn _range(_args: Vec<Value>) -> Result<Value, BjornError> {
+    arity("range", &_args, 1, 3)?;
+    let mut bounds = Vec::new();
+    for arg in &_args {
+        match arg {
+            Value::Int(i) => bounds.push(*i),
+            a => return Err(BjornError::TypeError(
+                format!("`range` arguments must be `int`, not `{}`", a.type_name()).into()
+            )),
+        }
+    }
+    let (start, stop, step) = match bounds.as_slice() {
+        [stop] => (0, *stop, 1),
+        [start, stop] => (*start, *stop, 1),
+        [start, stop, step] => (*start, *stop, *step),
+        _ => unreachable!(),
+    };
+    if step == 0 {
+        return Err(BjornError::RuntimeError("`range` step must not be zero".into()))
+    }
+    Ok(Value::Range(start, stop, step))
+}
+
+fn _len(_args: Vec<Value>) -> Result<Value, BjornError> {
+    arity("len", &_args, 1, 1)?;
+    let length = match &_args[0] {
+        Value::Str(s) => s.graphemes(true).count(),
+        Value::List(list) => list.borrow().len(),
+        Value::Tuple(tuple) => tuple.len(),
+        Value::Dict(dict) => dict.borrow().len(),
+        Value::Range(start, stop, step) => {
+            let length = range_length(*start, *stop, *step);
+            if length > i32::MAX as i64 {
+                return Err(BjornError::RuntimeError(
+                    "the length of the range is greater than the maximum integer".into()
+                ))
+            }
+            length as usize
+        },
+        a => return Err(BjornError::TypeError(
+            format!("`{}` has no length", a.type_name()).into()
+        )),
+    };
+    Ok(Value::Int(length as i32))
+}
+
+fn _divmod(_args: Vec<Value>) -> Result<Value, BjornError> {
+    arity("divmod", &_args, 2, 2)?;
+    let quotient = _args[0].clone().floor_div(_args[1].clone())?;
+    let remainder = (_args[0].clone() % _args[1].clone())?;
+    Ok(Value::tuple(vec![quotient, remainder]))
+}
+
+/// Number of integers in `range(start, stop, step)`, without going through them.
+fn range_length(start: i32, stop: i32, step: i32) -> i64 {
+    let (start, stop, step) = (start as i64, stop as i64, step as i64);
+    if step > 0 && start < stop {
+        (stop - start + step - 1) / step
+    } else if step < 0 && start > stop {
+        (start - stop - step - 1) / -step
+    } else {
+        0
+    }
 }
 
 
+#[derive(Default)]
 pub struct BuiltinsHandler {
     pub builtins: HashMap<String, Value>,
 }
@@ -22,5 +105,8 @@ impl BuiltinsHandler {
 
     pub fn register_builtins(&mut self) {
         self.builtins.insert(String::from("print"), Value::BuiltinFunction(_print));
+        self.builtins.insert(String::from("range"), Value::BuiltinFunction(_range));
+        self.builtins.insert(String::from("len"), Value::BuiltinFunction(_len));
+        self.builtins.insert(String::from("divmod"), Value::BuiltinFunction(_divmod));
     }
 }
@@ -0,0 +1,308 @@
+//!
+//! Lossless concrete syntax tree of the source code.
+//!
+//! Unlike the abstract syntax tree, it keeps every byte of the input,
+//! whitespace and comments included, so that the source code can be
+//! reproduced from it, say by a formatter or a refactoring tool.
+//! Statements and blocs are nodes, expressions are kept as flat tokens.
+//!
+
+use std::fmt;
+
+use lexer::{self, Lexer};
+use parser::Parser;
+use token::Token;
+use span::Span;
+use ast::AST;
+use error::BjornError;
+
+
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum SyntaxKind {
+    Program,
+    Bloc,
+    IfStatement,
+    WhileStatement,
+    ForStatement,
+    FunctionDeclaration,
+    ReturnStatement,
+    BreakStatement,
+    ContinueStatement,
+    GlobalStatement,
+    NonlocalStatement,
+    ExpressionStatement,
+}
+
+/// Token along with the exact text of the input it comes from.
+/// Tokens made up by the lexer, such as the dedents, have an empty text.
+#[derive(Debug, PartialEq, Clone)]
+pub struct SyntaxToken {
+    pub token: Token,
+    pub text: String,
+    pub span: Span,
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub enum SyntaxElement {
+    Node(SyntaxNode),
+    Token(SyntaxToken),
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub struct SyntaxNode {
+    pub kind: SyntaxKind,
+    pub children: Vec<SyntaxElement>,
+}
+
+impl SyntaxNode {
+
+    /// Tokens of the node in the order of the input, trivia included.
+    pub fn tokens(&self) -> Vec<&SyntaxToken> {
+        let mut tokens = Vec::new();
+        for child in &self.children {
+            match child {
+                SyntaxElement::Node(node) => tokens.extend(node.tokens()),
+                SyntaxElement::Token(token) => tokens.push(token),
+            }
+        }
+        tokens
+    }
+
+    /// Source code of the node, exactly as in the input.
+    pub fn text(&self) -> String {
+        self.tokens().iter().map(|token| token.text.as_str()).collect()
+    }
+
+    /// Lower the tree to the abstract syntax tree, dropping the trivia.
+    pub fn lower(&self) -> (AST, Vec<BjornError>) {
+        let tokens = self.tokens();
+        let end = match tokens.last() {
+            Some(last) => lexer::locate(&last.text, last.span),
+            None => Span::default(),
+        };
+        Parser::from_tokens(
+            tokens.into_iter().map(|token| (token.token.clone(), token.span)).collect(),
+            end
+        ).parse()
+    }
+}
+
+impl fmt::Display for SyntaxNode {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.text())
+    }
+}
+
+
+struct Builder {
+    tokens: Vec<SyntaxToken>,
+    position: usize,
+}
+
+impl Builder {
+
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.position).map(|token| &token.token)
+    }
+
+    /// Next token that is not trivia.
+    fn peek_significant(&self) -> Option<&Token> {
+        self.tokens[self.position..].iter()
+            .map(|token| &token.token)
+            .find(|token| !token.is_trivia())
+    }
+
+    fn advance(&mut self) -> SyntaxElement {
+        self.position += 1;
+        SyntaxElement::Token(self.tokens[self.position - 1].clone())
+    }
+
+    /// Advance up to the next token that is not trivia.
+    fn trivia(&mut self, children: &mut Vec<SyntaxElement>) {
+        while self.peek().is_some_and(|token| token.is_trivia()) {
+            children.push(self.advance());
+        }
+    }
+
+    fn program(&mut self) -> SyntaxNode {
+        let mut children = Vec::new();
+        while self.peek().is_some() {
+            children.push(self.element());
+        }
+        SyntaxNode {kind: SyntaxKind::Program, children: children}
+    }
+
+    fn element(&mut self) -> SyntaxElement {
+        match self.peek() {
+            Some(token) if token.is_trivia() => self.advance(),
+            // Tokens out of place, the parser reports them
+            Some(Token::NEWLINE) | Some(Token::INDENT) | Some(Token::DEDENT) => self.advance(),
+            _ => SyntaxElement::Node(self.statement()),
+        }
+    }
+
+    fn statement(&mut self) -> SyntaxNode {
+        let kind = match self.peek() {
+            Some(Token::IF) => SyntaxKind::IfStatement,
+            Some(Token::WHILE) => SyntaxKind::WhileStatement,
+            Some(Token::FOR) => SyntaxKind::ForStatement,
+            Some(Token::DEF) => SyntaxKind::FunctionDeclaration,
+            Some(Token::RETURN) => SyntaxKind::ReturnStatement,
+            Some(Token::BREAK) => SyntaxKind::BreakStatement,
+            Some(Token::CONTINUE) => SyntaxKind::ContinueStatement,
+            Some(Token::GLOBAL) => SyntaxKind::GlobalStatement,
+            Some(Token::NONLOCAL) => SyntaxKind::NonlocalStatement,
+            _ => SyntaxKind::ExpressionStatement,
+        };
+        let mut children = Vec::new();
+        while let Some(token) = self.peek().cloned() {
+            children.push(self.advance());
+            if token != Token::NEWLINE {
+                continue;
+            }
+            if self.peek_significant() != Some(&Token::INDENT) {
+                break;
+            }
+            self.trivia(&mut children);
+            children.push(SyntaxElement::Node(self.bloc()));
+            // `else` clauses belong to the `if` statement
+            if kind != SyntaxKind::IfStatement || self.peek_significant() != Some(&Token::ELSE) {
+                break;
+            }
+            self.trivia(&mut children);
+        }
+        SyntaxNode {kind: kind, children: children}
+    }
+
+    /// bloc: INDENT (NEWLINE | statement)* DEDENT
+    fn bloc(&mut self) -> SyntaxNode {
+        let mut children = vec![self.advance()];
+        while let Some(token) = self.peek() {
+            if *token == Token::DEDENT {
+                children.push(self.advance());
+                break;
+            }
+            children.push(self.element());
+        }
+        SyntaxNode {kind: SyntaxKind::Bloc, children: children}
+    }
+}
+
+///
+/// Build the concrete syntax tree of the input,
+/// along with the lexical errors met.
+/// Invalid text is kept in the tree as `ERROR` tokens.
+///
+/// ```
+/// extern crate bjorn;
+///
+/// let input = "def f(a):  # comment\n    return a\n";
+/// let (tree, errors) = bjorn::cst::parse(input);
+/// assert!(errors.is_empty());
+/// assert_eq!(tree.text(), input);
+/// ```
+///
+pub fn parse(input: &str) -> (SyntaxNode, Vec<BjornError>) {
+    let mut tokens = Vec::new();
+    let mut errors = Vec::new();
+    // Lexemes made up by the lexer may overlap the previous ones
+    let mut covered = 0;
+    for lexeme in Lexer::with_trivia(input) {
+        match lexeme {
+            Ok(lexeme) => for (token, span) in lexeme {
+                let start = span.start.max(covered);
+                let end = span.end.max(covered);
+                covered = end;
+                tokens.push(SyntaxToken {token: token, text: input[start..end].to_string(), span: span});
+            },
+            Err(error) => errors.push(error),
+        }
+    }
+    (Builder {tokens: tokens, position: 0}.program(), errors)
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn roundtrip(input: &str) {
+        let (tree, _) = parse(input);
+        assert_eq!(tree.text(), input);
+    }
+
+    fn kinds(node: &SyntaxNode) -> Vec<SyntaxKind> {
+        node.children.iter().filter_map(|child| match child {
+            SyntaxElement::Node(node) => Some(node.kind),
+            _ => None,
+        }).collect()
+    }
+
+    #[test]
+    fn lossless() {
+        roundtrip("");
+        roundtrip("  \n\n");
+        roundtrip("# only a comment");
+        roundtrip("a = 1  # one\n\n\n# two\nb = (a +\n     2)  \\\n  + 1\n");
+        roundtrip("\u{feff}if a:\r\n\tb = 'x'\r\n\r\n\t# c\r\nelse:\r\n\tc\r\n");
+        roundtrip("def f(a):\n    if a:\n        return 1\n    return \"\"\"\nmulti\nline\"\"\"\nf(1)");
+        roundtrip("while a:\n    b\n        c\n  d\n§ = 1\n");
+    }
+
+    #[test]
+    fn lossless_example() {
+        roundtrip(include_str!("../examples/fibonacci.bj"));
+    }
+
+    #[test]
+    fn structure() {
+        let (tree, errors) = parse("# f\ndef f(a):\n    if a:\n        return 1\n\n    else:\n        return 2\nf(1)\n");
+        assert!(errors.is_empty());
+        assert_eq!(kinds(&tree), vec![SyntaxKind::FunctionDeclaration, SyntaxKind::ExpressionStatement]);
+        match tree.children[2] {
+            SyntaxElement::Node(ref function) => {
+                let bloc = match function.children.last() {
+                    Some(SyntaxElement::Node(bloc)) => bloc,
+                    other => panic!("expected a bloc, got {:?}", other),
+                };
+                assert_eq!(kinds(bloc), vec![SyntaxKind::IfStatement]);
+                assert_eq!(bloc.text(), "    if a:\n        return 1\n\n    else:\n        return 2\n");
+            },
+            ref other => panic!("expected a function declaration, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn trivia() {
+        let (tree, _) = parse("a  # b\n");
+        let tokens: Vec<Token> = tree.tokens().into_iter().map(|token| token.token.clone()).collect();
+        assert_eq!(tokens, vec![
+            Token::ID(String::from("a")),
+            Token::WHITESPACE(String::from("  ")),
+            Token::COMMENT(String::from("# b")),
+            Token::NEWLINE,
+        ]);
+    }
+
+    #[test]
+    fn invalid_text() {
+        let (tree, errors) = parse("a = §\n");
+        assert_eq!(errors.len(), 1);
+        assert_eq!(tree.text(), "a = §\n");
+        assert!(tree.tokens().iter().any(|token| token.token == Token::ERROR(String::from("§"))));
+    }
+
+    #[test]
+    fn lowering() {
+        let inputs = [
+            include_str!("../examples/fibonacci.bj"),
+            "a = 1  # one\nb = (a +\n     2)\n",
+            "if a:\n  b\n\n  # c\nelse:\n\tc",
+            "if true\n    a = )\n",
+        ];
+        for input in &inputs {
+            let (tree, _) = parse(input);
+            assert_eq!(tree.lower(), ::parse(input));
+        }
+    }
+}
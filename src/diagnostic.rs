@@ -0,0 +1,406 @@
+//!
+//! Rendering of errors for humans and machines.
+//!
+
+use unicode_segmentation::UnicodeSegmentation;
+
+use error::{BjornError, BjornWarning, Diagnostic};
+use span::Span;
+
+
+const RED: &str = "\x1b[1;31m";
+const YELLOW: &str = "\x1b[1;33m";
+const BLUE: &str = "\x1b[1;34m";
+const BOLD: &str = "\x1b[1m";
+const RESET: &str = "\x1b[0m";
+
+struct Style {
+    color: bool,
+}
+
+impl Style {
+
+    fn paint(&self, style: &str, text: &str) -> String {
+        if self.color {
+            format!("{}{}{}", style, text, RESET)
+        } else {
+            text.to_string()
+        }
+    }
+}
+
+///
+/// Render an error the way a compiler would,
+/// with the offending line of `source` and a caret under the error location.
+///
+/// ```
+/// extern crate bjorn;
+///
+/// let input = "1 + true";
+/// let error = bjorn::interpret(input).unwrap_err();
+/// eprintln!("{}", bjorn::diagnostic::render(&error, "test.bj", input, false));
+/// ```
+///
+pub fn render(error: &BjornError, filename: &str, source: &str, color: bool) -> String {
+    let style = Style {color: color};
+    let mut output = render_traceback(error, filename, &style);
+    output.push_str(&render_diagnostic(
+        &format!("error[{}]", error.code()), RED, error.diagnostic(), filename, source, &style
+    ));
+    output
+}
+
+///
+/// Render a warning the same way as an error.
+///
+pub fn render_warning(warning: &BjornWarning, filename: &str, source: &str, color: bool) -> String {
+    let style = Style {color: color};
+    render_diagnostic(
+        &format!("warning[{}]", warning.code()), YELLOW, warning.diagnostic(), filename, source, &style
+    )
+}
+
+/// Render the message of a diagnostic titled `title`,
+/// painted with `level` like the caret under its location.
+fn render_diagnostic(
+    title: &str, level: &str, diagnostic: &Diagnostic, filename: &str, source: &str, style: &Style
+) -> String {
+    let mut output = format!(
+        "{}{}\n",
+        style.paint(level, title),
+        style.paint(BOLD, &format!(": {}", diagnostic.message)),
+    );
+
+    match diagnostic.span {
+        Some(span) => {
+            let line_number = span.line.to_string();
+            let gutter = " ".repeat(line_number.len());
+            let line = source_line(source, span.line);
+
+            // The caret underlines the span up to the end of its first line,
+            // padded like the line so that it stays aligned after tabs.
+            let padding: String = line.graphemes(true)
+                .take(span.column - 1)
+                .map(|grapheme| if grapheme == "\t" { '\t' } else { ' ' })
+                .collect();
+            let width = first_line(source.get(span.start..span.end).unwrap_or(""))
+                .graphemes(true)
+                .count()
+                .max(1);
+
+            output.push_str(&format!(
+                "{}{} {}:{}\n",
+                gutter, style.paint(BLUE, "-->"), filename, span
+            ));
+            output.push_str(&format!("{} {}\n", gutter, style.paint(BLUE, "|")));
+            output.push_str(&format!(
+                "{} {} {}\n", style.paint(BLUE, &line_number), style.paint(BLUE, "|"), line
+            ));
+            output.push_str(&format!(
+                "{} {} {}{}\n",
+                gutter,
+                style.paint(BLUE, "|"),
+                padding,
+                style.paint(level, &"^".repeat(width)),
+            ));
+            if let Some(ref help) = diagnostic.help {
+                output.push_str(&format!("{} {}\n", gutter, style.paint(BLUE, "|")));
+                output.push_str(&format!(
+                    "{} {} {}\n", gutter, style.paint(BLUE, "="), style.paint(BOLD, &format!("help: {}", help))
+                ));
+            }
+        },
+        None => {
+            output.push_str(&format!(" {} {}\n", style.paint(BLUE, "-->"), filename));
+            if let Some(ref help) = diagnostic.help {
+                output.push_str(&format!(
+                    " {} {}\n", style.paint(BLUE, "="), style.paint(BOLD, &format!("help: {}", help))
+                ));
+            }
+        },
+    }
+    output
+}
+
+/// Line `number` of the source, lines being ended by `\r\n`, `\r` or `\n` as in the lexer.
+fn source_line(source: &str, number: usize) -> &str {
+    let mut rest = source;
+    for _ in 1..number {
+        match rest.find(['\r', '\n']) {
+            Some(end) if rest[end..].starts_with("\r\n") => rest = &rest[end + 2..],
+            Some(end) => rest = &rest[end + 1..],
+            None => return "",
+        }
+    }
+    first_line(rest)
+}
+
+fn first_line(text: &str) -> &str {
+    text.find(['\r', '\n']).map_or(text, |end| &text[..end])
+}
+
+///
+/// Render the call frames of an error, the most recent call last.
+/// Each frame is located where the execution was in it:
+/// at the call of the next frame, or at the error for the last one.
+///
+fn render_traceback(error: &BjornError, filename: &str, style: &Style) -> String {
+    let traceback = error.traceback();
+    if traceback.is_empty() {
+        return String::new();
+    }
+
+    let mut output = format!("{}\n", style.paint(BOLD, "traceback (most recent call last):"));
+    let names = Some("<program>").into_iter()
+        .chain(traceback.iter().map(|frame| frame.function.as_str()));
+    let functions = Some(String::from("<program>")).into_iter()
+        .chain(traceback.iter().map(|frame| frame.to_string()));
+    let locations = traceback.iter().map(|frame| Some(frame.span))
+        .chain(Some(error.span()));
+    // A runaway recursion repeats the same frame, only with other arguments
+    let mut previous = None;
+    let mut repeated = 0;
+    for ((name, function), location) in names.zip(functions).zip(locations) {
+        if previous == Some((name, location)) {
+            repeated += 1;
+            continue;
+        }
+        push_repeated(&mut output, repeated);
+        repeated = 0;
+        previous = Some((name, location));
+        match location {
+            Some(span) => output.push_str(&format!("  {}:{}, in {}\n", filename, span, function)),
+            None => output.push_str(&format!("  {}, in {}\n", filename, function)),
+        }
+    }
+    push_repeated(&mut output, repeated);
+    output
+}
+
+fn push_repeated(output: &mut String, repeated: usize) {
+    if repeated > 0 {
+        output.push_str(&format!("  [previous frame repeated {} more times]\n", repeated));
+    }
+}
+
+///
+/// Render an error as a single line JSON object.
+///
+pub fn render_json(error: &BjornError, filename: &str) -> String {
+    let mut fields = json_fields(error.code(), error.name(), error.diagnostic(), filename);
+    if !error.traceback().is_empty() {
+        let frames: Vec<String> = error.traceback().iter().map(|frame| {
+            let arguments: Vec<String> = frame.arguments.iter()
+                .map(|argument| json_string(argument))
+                .collect();
+            format!(
+                "{{\"function\":{},\"arguments\":[{}],\"span\":{}}}",
+                json_string(&frame.function), arguments.join(","), json_span(frame.span)
+            )
+        }).collect();
+        fields.push(format!("\"traceback\":[{}]", frames.join(",")));
+    }
+    format!("{{{}}}", fields.join(","))
+}
+
+///
+/// Render a warning as a single line JSON object.
+///
+pub fn render_warning_json(warning: &BjornWarning, filename: &str) -> String {
+    let fields = json_fields(warning.code(), warning.name(), warning.diagnostic(), filename);
+    format!("{{{}}}", fields.join(","))
+}
+
+fn json_fields(code: &str, kind: &str, diagnostic: &Diagnostic, filename: &str) -> Vec<String> {
+    let mut fields = vec![
+        format!("\"code\":{}", json_string(code)),
+        format!("\"kind\":{}", json_string(kind)),
+        format!("\"message\":{}", json_string(&diagnostic.message)),
+        format!("\"file\":{}", json_string(filename)),
+    ];
+    if let Some(span) = diagnostic.span {
+        fields.push(format!("\"span\":{}", json_span(span)));
+    }
+    if let Some(ref help) = diagnostic.help {
+        fields.push(format!("\"help\":{}", json_string(help)));
+    }
+    fields
+}
+
+fn json_span(span: Span) -> String {
+    format!(
+        "{{\"start\":{},\"end\":{},\"line\":{},\"column\":{}}}",
+        span.start, span.end, span.line, span.column
+    )
+}
+
+fn json_string(input: &str) -> String {
+    let mut output = String::from("\"");
+    for c in input.chars() {
+        match c {
+            '"' => output.push_str("\\\""),
+            '\\' => output.push_str("\\\\"),
+            '\n' => output.push_str("\\n"),
+            '\r' => output.push_str("\\r"),
+            '\t' => output.push_str("\\t"),
+            c if (c as u32) < 0x20 => output.push_str(&format!("\\u{:04x}", c as u32)),
+            c => output.push(c),
+        }
+    }
+    output.push('"');
+    output
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use error::{BjornError, BjornWarning, Frame};
+
+    #[test]
+    fn render_with_span_and_help() {
+        let source = "a = 1\nif a\n    a\n";
+        let error = BjornError::SyntaxError("expected `:`, found newline".into())
+            .at(Span::new(10, 11, 2, 5))
+            .with_help("add `:` after the `if` condition");
+        assert_eq!(render(&error, "test.bj", source, false), "\
+error[E0002]: expected `:`, found newline
+ --> test.bj:2:5
+  |
+2 | if a
+  |     ^
+  |
+  = help: add `:` after the `if` condition
+");
+    }
+
+    #[test]
+    fn render_underlines_the_span() {
+        let source = "x = 1 + true";
+        let error = BjornError::TypeError("cannot add `int` and `bool`".into())
+            .at(Span::new(4, 12, 1, 5));
+        assert_eq!(render(&error, "test.bj", source, false), "\
+error[E0004]: cannot add `int` and `bool`
+ --> test.bj:1:5
+  |
+1 | x = 1 + true
+  |     ^^^^^^^^
+");
+    }
+
+    #[test]
+    fn render_with_cr_line_endings() {
+        let source = "a = 1\rb = a + true\r";
+        let error = BjornError::TypeError("cannot add `int` and `bool`".into())
+            .at(Span::new(10, 18, 2, 5));
+        assert_eq!(render(&error, "test.bj", source, false), "\
+error[E0004]: cannot add `int` and `bool`
+ --> test.bj:2:5
+  |
+2 | b = a + true
+  |     ^^^^^^^^
+");
+        assert_eq!(source_line("a\r\nb\rc\nd", 3), "c");
+        assert_eq!(source_line("a\r\nb\rc\nd", 4), "d");
+        assert_eq!(source_line("a\r\n", 3), "");
+    }
+
+    #[test]
+    fn render_after_tabs() {
+        let source = "if true:\n\ta = 1 + true\n";
+        let error = BjornError::TypeError("cannot add `int` and `bool`".into())
+            .at(Span::new(14, 22, 2, 6));
+        assert_eq!(render(&error, "test.bj", source, false), "\
+error[E0004]: cannot add `int` and `bool`
+ --> test.bj:2:6
+  |
+2 | \ta = 1 + true
+  | \t    ^^^^^^^^
+");
+    }
+
+    #[test]
+    fn render_without_span() {
+        let error = BjornError::RuntimeError("integer overflow in addition".into());
+        assert_eq!(
+            render(&error, "test.bj", "", false),
+            "error[E0007]: integer overflow in addition\n --> test.bj\n"
+        );
+    }
+
+    #[test]
+    fn render_with_color() {
+        let error = BjornError::RuntimeError("boom".into());
+        assert!(render(&error, "test.bj", "", true).starts_with("\x1b[1;31merror[E0007]"));
+    }
+
+    #[test]
+    fn json() {
+        let error = BjornError::NameError("name `a\"` is not defined".into())
+            .at(Span::new(0, 2, 1, 1));
+        assert_eq!(
+            render_json(&error, "test.bj"),
+            "{\"code\":\"E0005\",\"kind\":\"NameError\",\"message\":\"name `a\\\"` is not defined\",\
+\"file\":\"test.bj\",\"span\":{\"start\":0,\"end\":2,\"line\":1,\"column\":1}}"
+        );
+    }
+
+    #[test]
+    fn render_traceback() {
+        let source = "def f(n):\n    return n + true\nf(1)\n";
+        let error = BjornError::TypeError("cannot add `int` and `bool`".into())
+            .at(Span::new(21, 29, 2, 12))
+            .or_within(&[Frame::new(String::from("f"), Span::new(30, 34, 3, 1), vec![String::from("1")])]);
+        assert_eq!(render(&error, "test.bj", source, false), "\
+traceback (most recent call last):
+  test.bj:3:1, in <program>
+  test.bj:2:12, in f(1)
+error[E0004]: cannot add `int` and `bool`
+ --> test.bj:2:12
+  |
+2 |     return n + true
+  |            ^^^^^^^^
+");
+        assert!(render_json(&error, "test.bj").ends_with(
+            "\"traceback\":[{\"function\":\"f\",\"arguments\":[\"1\"],\"span\":{\"start\":30,\"end\":34,\"line\":3,\"column\":1}}]}"
+        ));
+    }
+
+    #[test]
+    fn render_repeated_frames() {
+        let source = "def f(n):\n    return f(n + 1)\nf(0)\n";
+        let frames: Vec<Frame> = Some(Frame::new(String::from("f"), Span::new(32, 36, 3, 1), vec![String::from("0")]))
+            .into_iter()
+            .chain((1..1000).map(|n| Frame::new(String::from("f"), Span::new(21, 29, 2, 12), vec![n.to_string()])))
+            .collect();
+        let error = BjornError::RuntimeError("maximum recursion depth exceeded".into())
+            .at(Span::new(21, 29, 2, 12))
+            .or_within(&frames);
+        assert_eq!(render(&error, "test.bj", source, false), "\
+traceback (most recent call last):
+  test.bj:3:1, in <program>
+  test.bj:2:12, in f(0)
+  [previous frame repeated 999 more times]
+error[E0007]: maximum recursion depth exceeded
+ --> test.bj:2:12
+  |
+2 |     return f(n + 1)
+  |            ^^^^^^^^
+");
+    }
+
+    #[test]
+    fn render_warnings() {
+        let warning = BjornWarning::ConfusableIdentifier("identifier `а` looks like `a`".into());
+        assert_eq!(
+            render_warning(&warning, "test.bj", "", false),
+            "warning[W0002]: identifier `а` looks like `a`\n --> test.bj\n"
+        );
+        assert_eq!(
+            render_warning_json(&warning, "test.bj"),
+            "{\"code\":\"W0002\",\"kind\":\"ConfusableIdentifier\",\
+\"message\":\"identifier `а` looks like `a`\",\"file\":\"test.bj\"}"
+        );
+    }
+}
@@ -0,0 +1,139 @@
+//!
+//! Dictionaries, iterated in the insertion order of their keys.
+//!
+
+use std::collections::HashMap;
+
+use error::BjornError;
+use value::Value;
+
+
+/// Hashable form of a key, equal for keys equal in the language.
+#[derive(Debug, PartialEq, Eq, Hash, Clone)]
+pub enum HashKey {
+    /// An integer and a float of the same value have the same key.
+    Number(u64),
+    Bool(bool),
+    Str(String),
+    Tuple(Vec<HashKey>),
+}
+
+impl HashKey {
+
+    pub fn new(key: &Value) -> Result<HashKey, BjornError> {
+        match key {
+            Value::Int(i) => HashKey::number(*i as f64),
+            Value::Float(f) => HashKey::number(*f),
+            Value::Bool(b) => Ok(HashKey::Bool(*b)),
+            Value::Str(s) => Ok(HashKey::Str(s.clone())),
+            Value::Tuple(tuple) => Ok(HashKey::Tuple(
+                tuple.iter().map(HashKey::new).collect::<Result<_, _>>()?
+            )),
+            a => Err(BjornError::TypeError(
+                format!("`{}` cannot be used as a key", a.type_name()).into()
+            ).with_help("only numbers, booleans, strings and tuples of them are hashable")),
+        }
+    }
+
+    fn number(f: f64) -> Result<HashKey, BjornError> {
+        // `NaN` is not equal to itself, it could never be found again
+        if f.is_nan() {
+            return Err(BjornError::TypeError("`NaN` cannot be used as a key".into()))
+        }
+        // `0.0` and `-0.0` are equal
+        let f = if f == 0.0 { 0.0 } else { f };
+        Ok(HashKey::Number(f.to_bits()))
+    }
+}
+
+
+#[derive(Debug, Clone, Default)]
+pub struct Dict {
+    entries: Vec<(Value, Value)>,
+    /// Position of the entry of each key.
+    positions: HashMap<HashKey, usize>,
+}
+
+impl Dict {
+
+    pub fn new() -> Dict {
+        Dict::default()
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    pub fn get(&self, key: &Value) -> Result<Option<&Value>, BjornError> {
+        let position = self.positions.get(&HashKey::new(key)?);
+        Ok(position.map(|position| &self.entries[*position].1))
+    }
+
+    /// Insert or replace the value of `key`, which keeps its position.
+    pub fn insert(&mut self, key: Value, value: Value) -> Result<(), BjornError> {
+        let hash_key = HashKey::new(&key)?;
+        match self.positions.get(&hash_key) {
+            Some(position) => self.entries[*position].1 = value,
+            None => {
+                self.positions.insert(hash_key, self.entries.len());
+                self.entries.push((key, value));
+            },
+        }
+        Ok(())
+    }
+
+    pub fn contains_key(&self, key: &Value) -> Result<bool, BjornError> {
+        Ok(self.positions.contains_key(&HashKey::new(key)?))
+    }
+
+    pub fn entries(&self) -> &[(Value, Value)] {
+        &self.entries
+    }
+
+    pub fn keys(&self) -> Vec<Value> {
+        self.entries.iter().map(|(key, _)| key.clone()).collect()
+    }
+
+    pub fn values(&self) -> Vec<Value> {
+        self.entries.iter().map(|(_, value)| value.clone()).collect()
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn hash_keys() {
+        assert_eq!(HashKey::new(&Value::Int(1)).unwrap(), HashKey::new(&Value::Float(1.0)).unwrap());
+        assert_eq!(HashKey::new(&Value::Float(0.0)).unwrap(), HashKey::new(&Value::Float(-0.0)).unwrap());
+        assert_ne!(HashKey::new(&Value::Int(1)).unwrap(), HashKey::new(&Value::Bool(true)).unwrap());
+        assert_ne!(HashKey::new(&Value::Int(1)).unwrap(), HashKey::new(&Value::Str(String::from("1"))).unwrap());
+        assert_eq!(
+            HashKey::new(&Value::tuple(vec![Value::Int(1), Value::Str(String::from("a"))])).unwrap(),
+            HashKey::new(&Value::tuple(vec![Value::Float(1.0), Value::Str(String::from("a"))])).unwrap()
+        );
+        assert!(HashKey::new(&Value::list(vec![])).is_err());
+        assert!(HashKey::new(&Value::tuple(vec![Value::list(vec![])])).is_err());
+        assert!(HashKey::new(&Value::Float(f64::NAN)).is_err());
+        assert!(HashKey::new(&Value::tuple(vec![Value::Float(f64::NAN)])).is_err());
+    }
+
+    #[test]
+    fn insertion_order() {
+        let mut dict = Dict::new();
+        dict.insert(Value::Str(String::from("b")), Value::Int(1)).unwrap();
+        dict.insert(Value::Str(String::from("a")), Value::Int(2)).unwrap();
+        dict.insert(Value::Float(1.0), Value::Int(3)).unwrap();
+        dict.insert(Value::Str(String::from("b")), Value::Int(4)).unwrap();
+        dict.insert(Value::Int(1), Value::Int(5)).unwrap();
+        assert_eq!(dict.keys(), vec![Value::Str(String::from("b")), Value::Str(String::from("a")), Value::Float(1.0)]);
+        assert_eq!(dict.values(), vec![Value::Int(4), Value::Int(2), Value::Int(5)]);
+        assert_eq!(dict.get(&Value::Int(1)).unwrap(), Some(&Value::Int(5)));
+    }
+}
@@ -0,0 +1,231 @@
+use std::error;
+use std::fmt;
+
+use span::Span;
+
+
+/// Function call being executed when an error was raised.
+#[derive(Debug, PartialEq, Clone)]
+pub struct Frame {
+    pub function: String,
+    /// Location of the call.
+    pub span: Span,
+    /// Values of the arguments, as they would be printed.
+    pub arguments: Vec<String>,
+}
+
+impl Frame {
+
+    pub fn new(function: String, span: Span, arguments: Vec<String>) -> Frame {
+        Frame {function: function, span: span, arguments: arguments}
+    }
+}
+
+impl fmt::Display for Frame {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}({})", self.function, self.arguments.join(", "))
+    }
+}
+
+
+/// What is known about an error besides its kind.
+#[derive(Debug, PartialEq, Clone)]
+pub struct Diagnostic {
+    pub message: String,
+    pub span: Option<Span>,
+    pub help: Option<String>,
+    /// Call frames from the outermost to the innermost.
+    pub traceback: Vec<Frame>,
+}
+
+impl Diagnostic {
+
+    pub fn new(message: String) -> Diagnostic {
+        Diagnostic {message: message, span: None, help: None, traceback: Vec::new()}
+    }
+}
+
+impl<'a> From<&'a str> for Diagnostic {
+    fn from(message: &'a str) -> Diagnostic {
+        Diagnostic::new(String::from(message))
+    }
+}
+
+impl From<String> for Diagnostic {
+    fn from(message: String) -> Diagnostic {
+        Diagnostic::new(message)
+    }
+}
+
+
+#[derive(Debug, PartialEq, Clone)]
+pub enum BjornError {
+    LexicalError(Diagnostic),
+    SyntaxError(Diagnostic),
+    IndentationError(Diagnostic),
+    TypeError(Diagnostic),
+    NameError(Diagnostic),
+    ArityError(Diagnostic),
+    RuntimeError(Diagnostic),
+}
+
+impl BjornError {
+
+    pub fn name(&self) -> &str {
+        match self {
+            BjornError::LexicalError(_) => "LexicalError",
+            BjornError::SyntaxError(_) => "SyntaxError",
+            BjornError::IndentationError(_) => "IndentationError",
+            BjornError::TypeError(_) => "TypeError",
+            BjornError::NameError(_) => "NameError",
+            BjornError::ArityError(_) => "ArityError",
+            BjornError::RuntimeError(_) => "RuntimeError",
+        }
+    }
+
+    /// Stable identifier of the kind of error, shown in diagnostics.
+    pub fn code(&self) -> &str {
+        match self {
+            BjornError::LexicalError(_) => "E0001",
+            BjornError::SyntaxError(_) => "E0002",
+            BjornError::IndentationError(_) => "E0003",
+            BjornError::TypeError(_) => "E0004",
+            BjornError::NameError(_) => "E0005",
+            BjornError::ArityError(_) => "E0006",
+            BjornError::RuntimeError(_) => "E0007",
+        }
+    }
+
+    pub fn diagnostic(&self) -> &Diagnostic {
+        match self {
+            BjornError::LexicalError(diagnostic)
+            | BjornError::SyntaxError(diagnostic)
+            | BjornError::IndentationError(diagnostic)
+            | BjornError::TypeError(diagnostic)
+            | BjornError::NameError(diagnostic)
+            | BjornError::ArityError(diagnostic)
+            | BjornError::RuntimeError(diagnostic) => diagnostic,
+        }
+    }
+
+    fn diagnostic_mut(&mut self) -> &mut Diagnostic {
+        match self {
+            BjornError::LexicalError(diagnostic)
+            | BjornError::SyntaxError(diagnostic)
+            | BjornError::IndentationError(diagnostic)
+            | BjornError::TypeError(diagnostic)
+            | BjornError::NameError(diagnostic)
+            | BjornError::ArityError(diagnostic)
+            | BjornError::RuntimeError(diagnostic) => diagnostic,
+        }
+    }
+
+    pub fn message(&self) -> &str {
+        &self.diagnostic().message
+    }
+
+    pub fn span(&self) -> Option<Span> {
+        self.diagnostic().span
+    }
+
+    pub fn help(&self) -> Option<&str> {
+        self.diagnostic().help.as_ref().map(|help| help.as_ref())
+    }
+
+    /// Call frames the error was raised from, outermost first.
+    pub fn traceback(&self) -> &[Frame] {
+        &self.diagnostic().traceback
+    }
+
+    /// Locate the error at `span`.
+    pub fn at(mut self, span: Span) -> BjornError {
+        self.diagnostic_mut().span = Some(span);
+        self
+    }
+
+    /// Locate the error at `span` unless it is already located.
+    pub fn or_at(mut self, span: Span) -> BjornError {
+        if self.diagnostic().span.is_none() {
+            self.diagnostic_mut().span = Some(span);
+        }
+        self
+    }
+
+    pub fn with_help(mut self, help: &str) -> BjornError {
+        self.diagnostic_mut().help = Some(String::from(help));
+        self
+    }
+
+    /// Attach the call `frames` unless the error already has a traceback.
+    pub fn or_within(mut self, frames: &[Frame]) -> BjornError {
+        if self.diagnostic().traceback.is_empty() {
+            self.diagnostic_mut().traceback = frames.to_vec();
+        }
+        self
+    }
+}
+
+impl fmt::Display for BjornError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.span() {
+            Some(span) => write!(f, "{}: {} at {}", self.name(), self.message(), span),
+            None => write!(f, "{}: {}", self.name(), self.message()),
+        }
+    }
+}
+
+impl error::Error for BjornError {}
+
+
+/// Suspicious construct that does not prevent the program from running.
+#[derive(Debug, PartialEq, Clone)]
+pub enum BjornWarning {
+    MixedScriptIdentifier(Diagnostic),
+    ConfusableIdentifier(Diagnostic),
+}
+
+impl BjornWarning {
+
+    pub fn name(&self) -> &str {
+        match self {
+            BjornWarning::MixedScriptIdentifier(_) => "MixedScriptIdentifier",
+            BjornWarning::ConfusableIdentifier(_) => "ConfusableIdentifier",
+        }
+    }
+
+    /// Stable identifier of the kind of warning, shown in diagnostics.
+    pub fn code(&self) -> &str {
+        match self {
+            BjornWarning::MixedScriptIdentifier(_) => "W0001",
+            BjornWarning::ConfusableIdentifier(_) => "W0002",
+        }
+    }
+
+    pub fn diagnostic(&self) -> &Diagnostic {
+        match self {
+            BjornWarning::MixedScriptIdentifier(diagnostic)
+            | BjornWarning::ConfusableIdentifier(diagnostic) => diagnostic,
+        }
+    }
+
+    pub fn message(&self) -> &str {
+        &self.diagnostic().message
+    }
+
+    pub fn span(&self) -> Option<Span> {
+        self.diagnostic().span
+    }
+
+    pub fn help(&self) -> Option<&str> {
+        self.diagnostic().help.as_ref().map(|help| help.as_ref())
+    }
+}
+
+impl fmt::Display for BjornWarning {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.span() {
+            Some(span) => write!(f, "{}: {} at {}", self.name(), self.message(), span),
+            None => write!(f, "{}: {}", self.name(), self.message()),
+        }
+    }
+}
@@ -1,301 +1,636 @@
 use std::collections::HashMap;
-use std::collections::hash_map::Entry;
+use std::cmp::Ordering;
+use std::rc::Rc;
+use std::cell::RefCell;
 
 use token::Token;
 use parser::Parser;
 use ast::AST;
 use memory::Memory;
-use value::Value;
+use value::{Function, Value};
+use dict::Dict;
+use span::Span;
 use builtins::BuiltinsHandler;
+use error::{BjornError, Frame};
+use suggestion::suggest;
 
 
+/// Function calls that can be nested before the recursion is considered runaway.
+const MAX_DEPTH: usize = 1000;
+
+/// Stack a program is parsed and run with, enough for `MAX_DEPTH` nested calls
+/// and the deepest nesting the parser accepts, even in debug builds.
+pub const STACK_SIZE: usize = 256 * 1024 * 1024;
+
+/// Place an assignment stores a value in.
+enum Target {
+    Variable(String),
+    /// Element of a collection at an index.
+    Item(Value, Value),
+    /// Targets the elements of an iterable are unpacked into,
+    /// the starred one, if any, takes the remaining elements as a list.
+    Unpack {targets: Vec<AST>, starred: Option<usize>},
+}
+
+/// How the execution goes on after a statement.
+#[derive(Debug, PartialEq)]
+enum Flow {
+    Normal,
+    Return(Value),
+    Break,
+    Continue,
+}
+
+/// Function call being executed, rendered as a `Frame` only when an error is raised.
+struct Call {
+    function: String,
+    span: Span,
+    arguments: Vec<Value>,
+}
+
+impl Call {
+    fn frame(&self) -> Frame {
+        let arguments = self.arguments.iter().map(|argument| argument.to_string()).collect();
+        Frame::new(self.function.clone(), self.span, arguments)
+    }
+}
+
 pub struct Interpreter<'a> {
     parser: Parser<'a>,
     pub memory: Memory,
+    /// Function calls being executed, alongside the memory scopes.
+    calls: Vec<Call>,
 }
 
 impl<'a> Interpreter<'a> {
 
     pub fn new(parser: Parser<'a>) -> Interpreter<'a>  {
-        Interpreter { parser: parser, memory: Memory::new(HashMap::new()) }
+        Interpreter { parser: parser, memory: Memory::new(HashMap::new()), calls: Vec::new() }
     }
 
     fn load_builtins(&mut self) {
 
         let mut builtins_handler = BuiltinsHandler::new();
         builtins_handler.register_builtins();
-        self.memory.current_scope_mut().unwrap().extend(builtins_handler.builtins);
+        self.memory.builtins_mut().extend(builtins_handler.builtins);
     }
 
-    fn load_functions(&mut self, tree: AST) {
-        match tree {
-            AST::Program {children} => {
-                for child in children {
-                    self.load_functions(*child);
+    /// Define the top-level functions, which can be called before their declaration.
+    fn load_functions(&mut self, tree: AST) -> Result<(), BjornError> {
+        if let AST::Program {children, ..} = tree {
+            for child in children {
+                if let AST::FunctionDeclaration {..} = *child {
+                    self.execute(*child)?;
                 }
+            }
+        }
+        Ok(())
+    }
+
+    /// Function value for a declaration or a lambda, enclosed by the current scope.
+    /// The parser checks that the parameters are distinct identifiers.
+    fn function(&self, name: String, parameters: Vec<AST>, body: AST) -> Value {
+        let names = parameters.into_iter().filter_map(|parameter| match parameter {
+            AST::Parameter {parameter, ..} => match *parameter {
+                AST::Variable {id, ..} => id.identifier(),
+                _ => None,
             },
-            AST::FunctionDeclaration {identifier, parameters, body} => {
-                let function_name = identifier.identifier().unwrap();
-                self.memory.insert(function_name,
-                    Value::Function(AST::FunctionDeclaration {identifier, parameters, body}));
-            },
-            _ => {}
+            _ => None,
+        }).collect();
+        Value::Function(Rc::new(Function {
+            name: name,
+            parameters: names,
+            body: body,
+            environment: self.memory.environment(),
+        }))
+    }
+
+    fn call(&mut self, callee: Value, arguments: Vec<Value>, span: Span) -> Result<Value, BjornError> {
+        let function = match callee {
+            // Builtins check their arguments themselves
+            Value::BuiltinFunction(builtin) => return builtin(arguments),
+            Value::Function(function) => function,
+            Value::Method(value, name) => return value.call_method(&name, arguments),
+            value => return Err(BjornError::TypeError(
+                format!("`{}` is not callable", value.type_name()).into()
+            )),
+        };
+        if self.calls.len() >= MAX_DEPTH {
+            return Err(BjornError::RuntimeError(
+                "maximum recursion depth exceeded".into()
+            ).with_help(&format!("calls can be nested up to {} times", MAX_DEPTH)))
+        }
+        if arguments.len() != function.parameters.len() {
+            return Err(BjornError::ArityError(format!(
+                "`{}` takes {} argument(s) but {} were given",
+                function.name, function.parameters.len(), arguments.len()
+            ).into()))
+        }
+
+        let call = Call {function: function.name.clone(), span: span, arguments: arguments.clone()};
+        let variables = function.parameters.iter().cloned().zip(arguments).collect();
+        self.calls.push(call);
+        self.memory.push_scope(variables, &function.environment);
+        let result = self.execute(function.body.clone());
+        self.memory.pop_scope();
+        self.calls.pop();
+        match result? {
+            Flow::Return(value) => Ok(value),
+            _ => Ok(Value::None),
         }
     }
 
-    fn visit(&mut self, tree: AST) -> Value {
+    fn visit(&mut self, tree: AST) -> Result<Value, BjornError> {
+        // Errors are located at the innermost node they are raised from,
+        // and get the call frames active at that point.
+        let span = tree.span();
+        match self.visit_node(tree) {
+            Ok(value) => Ok(value),
+            Err(error) => Err(self.locate(error, span)),
+        }
+    }
+
+    fn execute(&mut self, tree: AST) -> Result<Flow, BjornError> {
+        let span = tree.span();
+        match self.execute_node(tree) {
+            Ok(flow) => Ok(flow),
+            Err(error) => Err(self.locate(error, span)),
+        }
+    }
+
+    /// Locate an error at `span` and attach the calls being executed, unless already done.
+    fn locate(&self, error: BjornError, span: Span) -> BjornError {
+        let error = error.or_at(span);
+        if !error.traceback().is_empty() || self.calls.is_empty() {
+            return error
+        }
+        let frames: Vec<Frame> = self.calls.iter().map(Call::frame).collect();
+        error.or_within(&frames)
+    }
+
+    /// Execute a statement, telling whether the enclosing blocs go on.
+    ///
+    /// Statements and expressions recurse through this method and `visit_node`,
+    /// so the work of each node is done in its own method to keep their stack frames small.
+    fn execute_node(&mut self, tree: AST) -> Result<Flow, BjornError> {
         match tree {
-            AST::Program {children} => {
-                // Return the value the last child mostly for testing purposes.
-                // WIll be replaced by eval input in the future.
-                let mut result = Value::None;
-
-                for child in children {
-                    match *child {
-                        AST::FunctionDeclaration {..} => {},
-                        _ => {
-                            result = self.visit(*child);
-                        },
-                    };
-                }
-                result
+            AST::Bloc {children, ..} => self.bloc(children),
+            AST::IfStatement {if_compound, else_if_compounds, else_compound, ..} => {
+                self.if_statement(if_compound, else_if_compounds, *else_compound)
             },
-            AST::FunctionDeclaration {identifier: _, parameters, body} => {
-                for (i, parameter) in parameters.into_iter().enumerate() {
-                    let parameter_name = match parameter {
-                        AST::Parameter {parameter} => match *parameter {
-                            AST::Variable {id} => id.identifier().unwrap(),
-                            _ => panic!("Interpreter error.")
-                        },
-                        AST::Empty => break,
-                        _ => panic!("Interpreter Error.")
-                    };
-                    let parameter_value = match self.memory.remove(i.to_string()) {
-                        Some(value) => value,
-                        None => panic!("Interpreter error.")
-                    };
-                    match self.memory.current_scope_mut().unwrap().entry(parameter_name) {
-                        Entry::Occupied(_) => panic!("Interpreter error."),
-                        Entry::Vacant(v) => v.insert(parameter_value)
-                    };
-                }
-                self.visit(*body)
+            AST::WhileStatement {condition, bloc, ..} => self.while_statement(*condition, *bloc),
+            AST::ForStatement {variable, iterable, bloc, ..} => self.for_statement(*variable, *iterable, *bloc),
+            AST::ReturnStatement {expression, ..} => Ok(Flow::Return(self.visit(*expression)?)),
+            AST::FunctionDeclaration {identifier, parameters, body, ..} => {
+                self.function_declaration(identifier, parameters, *body)
             },
-            AST::Parameter {parameter} => {
-                self.visit(*parameter)
+            AST::BreakStatement {..} => Ok(Flow::Break),
+            AST::ContinueStatement {..} => Ok(Flow::Continue),
+            AST::GlobalStatement {names, ..} => self.global_statement(names),
+            AST::NonlocalStatement {names, ..} => self.nonlocal_statement(names),
+            _ => {
+                self.visit(tree)?;
+                Ok(Flow::Normal)
             },
-            AST::IfStatement {if_compound, else_if_compounds, else_compound} => {
-                let (if_condition, if_bloc) = if_compound;
-                if self.visit(*if_condition.clone()) == Value::Bool(true) {
-                    self.visit(*if_bloc.clone())
-                } else {
-                    for else_if_compound in &else_if_compounds {
-                        let (else_if_condition, else_if_bloc) = else_if_compound;
-                        if self.visit(*else_if_condition.clone()) == Value::Bool(true) {
-                            let result_else_if_bloc = self.visit(*else_if_bloc.clone());
-                            match result_else_if_bloc {
-                                Value::None => {},
-                                _ => return result_else_if_bloc
-                            };
-                        }
-                    }
-                    self.visit(*else_compound)
-                }
+        }
+    }
+
+    fn bloc(&mut self, children: Vec<Box<AST>>) -> Result<Flow, BjornError> {
+        for child in children {
+            match self.execute(*child)? {
+                Flow::Normal => {},
+                flow => return Ok(flow),
+            };
+        }
+        Ok(Flow::Normal)
+    }
+
+    fn if_statement(&mut self, if_compound: (Box<AST>, Box<AST>), else_if_compounds: Vec<(Box<AST>, Box<AST>)>, else_compound: AST) -> Result<Flow, BjornError> {
+        let (if_condition, if_bloc) = if_compound;
+        if self.visit(*if_condition)? == Value::Bool(true) {
+            return self.execute(*if_bloc)
+        }
+        for else_if_compound in else_if_compounds {
+            let (else_if_condition, else_if_bloc) = else_if_compound;
+            if self.visit(*else_if_condition)? == Value::Bool(true) {
+                return self.execute(*else_if_bloc)
+            }
+        }
+        self.execute(else_compound)
+    }
+
+    fn while_statement(&mut self, condition: AST, bloc: AST) -> Result<Flow, BjornError> {
+        while self.visit(condition.clone())? == Value::Bool(true) {
+            match self.execute(bloc.clone())? {
+                Flow::Return(value) => return Ok(Flow::Return(value)),
+                Flow::Break => break,
+                Flow::Normal | Flow::Continue => {},
+            };
+        }
+        Ok(Flow::Normal)
+    }
+
+    fn for_statement(&mut self, variable: AST, iterable: AST, bloc: AST) -> Result<Flow, BjornError> {
+        for value in self.visit(iterable)?.iter()? {
+            let target = self.target(variable.clone())?;
+            self.store(target, value)?;
+            match self.execute(bloc.clone())? {
+                Flow::Return(value) => return Ok(Flow::Return(value)),
+                Flow::Break => break,
+                Flow::Normal | Flow::Continue => {},
+            };
+        }
+        Ok(Flow::Normal)
+    }
+
+    fn function_declaration(&mut self, identifier: Token, parameters: Vec<AST>, body: AST) -> Result<Flow, BjornError> {
+        let function_name = identifier.identifier().unwrap();
+        let function = self.function(function_name.clone(), parameters, body);
+        self.memory.insert(function_name, function);
+        Ok(Flow::Normal)
+    }
+
+    /// As in Python, a name cannot be declared once bound in the current function scope.
+    fn global_statement(&mut self, names: Vec<Token>) -> Result<Flow, BjornError> {
+        for name in names {
+            let name = name.identifier().unwrap();
+            if !self.memory.is_global() && self.memory.is_local(&name) {
+                return Err(declared_after_use(&name, "global"))
+            }
+            self.memory.declare_global(name);
+        }
+        Ok(Flow::Normal)
+    }
+
+    fn nonlocal_statement(&mut self, names: Vec<Token>) -> Result<Flow, BjornError> {
+        for name in names {
+            let name = name.identifier().unwrap();
+            if self.memory.is_local(&name) {
+                return Err(declared_after_use(&name, "nonlocal"))
+            }
+            if !self.memory.declare_nonlocal(name.clone()) {
+                return Err(BjornError::SyntaxError(
+                    format!("no binding for nonlocal `{}` found", name).into()
+                ))
+            }
+        }
+        Ok(Flow::Normal)
+    }
+
+    fn visit_node(&mut self, tree: AST) -> Result<Value, BjornError> {
+        match tree {
+            AST::Program {children, ..} => self.program(children),
+            AST::Parameter {parameter, ..} => self.visit(*parameter),
+            AST::Assignment {left, right, ..} => self.assignment(*left, *right),
+            AST::AugmentedAssignment {left, op, right, ..} => self.augmented_assignment(*left, op, *right),
+            AST::List {elements, ..} => Ok(Value::list(self.elements(elements)?)),
+            AST::Tuple {elements, ..} => Ok(Value::tuple(self.elements(elements)?)),
+            AST::Starred {..} => Err(BjornError::SyntaxError(
+                "starred expressions can only be assignment targets".into()
+            )),
+            AST::Dict {entries, ..} => self.dict(entries),
+            AST::Attribute {value, name, ..} => {
+                self.visit(*value)?.attribute(&name.identifier().unwrap())
             },
-            AST::WhileStatement {condition, bloc} => {
-                loop {
-                    if self.visit(*condition.clone()) == Value::Bool(true) {
-                        let result_loop = self.visit(*bloc.clone());
-                        match result_loop {
-                            Value::None => {},
-                            _ => return result_loop
-                        };
-                    } else {
-                        break;
-                    }
-                }
-                Value::None
+            AST::Index {value, index, ..} => self.index(*value, *index),
+            AST::Conditional {condition, then_branch, else_branch, ..} => {
+                self.conditional(*condition, *then_branch, *else_branch)
             },
-            AST::Bloc {children} => {
-                let mut result = Value::None;
-                for child in children {
-                    match *child {
-                        AST::ReturnStatement {..} => {
-                            result = self.visit(*child);
-                            break;
-                        },
-                        AST::IfStatement{..} => {
-                            result = self.visit(*child);
-                            match result {
-                                Value::None => {},
-                                _ => return result
-                            };
-                        },
-                        AST::WhileStatement{..} => {
-                            result = self.visit(*child);
-                            match result {
-                                Value::None => {},
-                                _ => return result
-                            };
-                        },
-                        _ => {
-                            self.visit(*child);
-                        },
-                    };
+            AST::BinaryOperation {left, op, right, ..} => self.binary_operation(*left, op, *right),
+            AST::Comparison {operands, operators, ..} => self.comparison(operands, operators),
+            AST::UnaryOperation {op, right, ..} => self.unary_operation(op, *right),
+            AST::IntNumber {token, ..} => {
+                match token.integer() {
+                    Some(integer) => Ok(Value::Int(integer)),
+                    None => Err(BjornError::RuntimeError(
+                        format!("{} is out of range", token).into()
+                    )),
                 }
-                result
-            },
-            AST::ReturnStatement {expression} => {
-                self.visit(*expression)
             },
-            AST::Assignment {left, right} => {
-                let variable_name = match *left {
-                    AST::Variable{id} => id.identifier().unwrap(),
-                    _ => panic!("Interpreter error."),
-                };
-                let variable_value = self.visit(*right);
-
-                self.memory.insert(variable_name, variable_value);
-                Value::None
+            AST::FloatNumber {token, ..} => {
+                Ok(Value::Float(token.float().unwrap()))
             },
-            AST::BinaryOperation {left, op, right} => { // TODO Try to use `match` statement
-                if op == Token::PLUS {
-                    self.visit(*left) + self.visit(*right)
-                } else if op == Token::MINUS {
-                    self.visit(*left) - self.visit(*right)
-                } else if op == Token::MUL {
-                    self.visit(*left) * self.visit(*right)
-                } else if op == Token::DIV {
-                    self.visit(*left) / self.visit(*right)
-                } else if op == Token::EQ {
-                    Value::Bool(self.visit(*left) == self.visit(*right))
-                } else if op == Token::NE {
-                    Value::Bool(self.visit(*left) != self.visit(*right))
-                } else if op == Token::LE {
-                    Value::Bool(self.visit(*left) <= self.visit(*right))
-                } else if op == Token::GE {
-                    Value::Bool(self.visit(*left) >= self.visit(*right))
-                } else if op == Token::LT {
-                    Value::Bool(self.visit(*left) < self.visit(*right))
-                } else if op == Token::GT {
-                    Value::Bool(self.visit(*left) > self.visit(*right))
-                } else if op == Token::OR {
-                    match (self.visit(*left), self.visit(*right)) {
-                        (Value::Bool(a), Value::Bool(b)) => Value::Bool(a || b),
-                        (_, _) => panic!("Invalid operation."),
-                    }
-                } else if op == Token::AND {
-                    match (self.visit(*left), self.visit(*right)) {
-                        (Value::Bool(a), Value::Bool(b)) => Value::Bool(a && b),
-                        (_, _) => panic!("Invalid operation."),
-                    }
-                } else {
-                    panic!("Interpreter error.")
-                }
+            AST::String {token, ..} => {
+                Ok(Value::Str(token.string().unwrap()))
             },
-            AST::UnaryOperation {op, right} => {
-                if op == Token::PLUS {
-                    self.visit(*right)
-                } else if op == Token::MINUS {
-                    -self.visit(*right)
-                } else if op == Token::NOT {
-                    !self.visit(*right)
-                } else {
-                    panic!("Interpreter error.")
-                }
+            AST::Boolean {token, ..} => {
+                Ok(Value::Bool(token.boolean().unwrap()))
             },
-            AST::IntNumber {token} => {
-                Value::Int(token.integer().unwrap())
+            AST::FunctionCall {callee, arguments, span} => self.function_call(*callee, arguments, span),
+            AST::Lambda {parameters, body, ..} => {
+                let body = AST::ReturnStatement {span: body.span(), expression: body};
+                Ok(self.function(String::from("<lambda>"), parameters, body))
             },
-            AST::FloatNumber {token} => {
-                Value::Float(token.float().unwrap())
+            AST::Variable {id, ..} => self.variable(id),
+            AST::Error {..} => Err(BjornError::SyntaxError(
+                "cannot execute invalid code".into()
+            )),
+            _ => Ok(Value::None)
+        }
+    }
+
+    fn program(&mut self, children: Vec<Box<AST>>) -> Result<Value, BjornError> {
+        // Return the value the last child mostly for testing purposes.
+        // WIll be replaced by eval input in the future.
+        let mut result = Value::None;
+
+        for child in children {
+            match *child {
+                AST::FunctionDeclaration {..} => {},
+                AST::IfStatement {..}
+                | AST::WhileStatement {..}
+                | AST::ForStatement {..}
+                | AST::ReturnStatement {..}
+                | AST::BreakStatement {..}
+                | AST::ContinueStatement {..}
+                | AST::GlobalStatement {..}
+                | AST::NonlocalStatement {..} => {
+                    result = match self.execute(*child)? {
+                        Flow::Return(value) => value,
+                        _ => Value::None,
+                    };
+                },
+                _ => {
+                    result = self.visit(*child)?;
+                },
+            };
+        }
+        Ok(result)
+    }
+
+    fn assignment(&mut self, left: AST, right: AST) -> Result<Value, BjornError> {
+        let value = self.visit(right)?;
+        let target = self.target(left)?;
+        self.store(target, value)?;
+        Ok(Value::None)
+    }
+
+    fn augmented_assignment(&mut self, left: AST, op: Token, right: AST) -> Result<Value, BjornError> {
+        // The target is evaluated once, before the value
+        let target = self.target(left)?;
+        let current = self.load(&target)?;
+        let value = self.visit(right)?;
+        let result = binary_operation(current, &op, value)?;
+        self.store(target, result)?;
+        Ok(Value::None)
+    }
+
+    fn elements(&mut self, elements: Vec<AST>) -> Result<Vec<Value>, BjornError> {
+        let mut values = Vec::new();
+        for element in elements {
+            values.push(self.visit(element)?);
+        }
+        Ok(values)
+    }
+
+    fn dict(&mut self, entries: Vec<(AST, AST)>) -> Result<Value, BjornError> {
+        let mut dict = Dict::new();
+        for (key, value) in entries {
+            let key = self.visit(key)?;
+            let value = self.visit(value)?;
+            dict.insert(key, value)?;
+        }
+        Ok(Value::Dict(Rc::new(RefCell::new(dict))))
+    }
+
+    fn index(&mut self, value: AST, index: AST) -> Result<Value, BjornError> {
+        let value = self.visit(value)?;
+        match index {
+            AST::Slice {start, stop, step, ..} => {
+                let start = self.slice_bound(*start)?;
+                let stop = self.slice_bound(*stop)?;
+                let step = self.slice_bound(*step)?;
+                value.slice(start, stop, step)
             },
-            AST::Boolean {token} => {
-                Value::Bool(token.boolean().unwrap())
+            index => {
+                let index = self.visit(index)?;
+                value.index(&index)
             },
-            AST::FunctionCall {identifier, arguments} => {
-                let function_name = identifier.identifier().unwrap();
-                let mut is_builtin = false;
-                let function_ast = match self.memory.get(function_name.clone()) {
-                    Some(Value::Function(ast)) => {
-                        ast.clone()
-                    },
-                    Some(Value::BuiltinFunction(_)) => {
-                        // For now we are only dealing with one argument builtins functions
-                        // if arguments.len() != 1 {
-                        //     panic!("Interpreter error.")
-                        // }
-                        is_builtin = true;
-                        let first_argument = arguments.first();
-                        match first_argument {
-                            Some(argument) => {
-                                argument.clone()
-                            },
-                            None => panic!("Interpreter error."),
-                        }
-                    },
-                    _ => panic!("Interpreter Error.")
-                };
-
-                if is_builtin {
-                    let builtin = match function_ast.clone() {
-                        AST::Parameter {..} => {
-                            match self.memory.get(function_name) {
-                                Some(Value::BuiltinFunction(builtin)) => builtin.clone(),
-                                _ => panic!("Interpreter error.")
-                            }
-                        },
-                        _ => panic!("Interpreter error.")
-                    };
+        }
+    }
 
-                    match function_ast {
-                        AST::Parameter {parameter} => {
-                            // For now we are only dealing with one argument builtins functions
-                            return builtin(self.visit(*parameter));
-                        },
-                        _ => panic!("Interpreter error.")
-                    };
-                }
+    fn conditional(&mut self, condition: AST, then_branch: AST, else_branch: AST) -> Result<Value, BjornError> {
+        // Only the selected branch is evaluated
+        if self.visit(condition)? == Value::Bool(true) {
+            self.visit(then_branch)
+        } else {
+            self.visit(else_branch)
+        }
+    }
 
-                match function_ast {
-                    AST::FunctionDeclaration {identifier: _, ref parameters, ..} => {
-                        if arguments.len() != parameters.len() {
-                            panic!("Interpreter error.")
-                        }
-                    },
-                    _ => panic!("Interpreter error.")
-                }
+    fn binary_operation(&mut self, left: AST, op: Token, right: AST) -> Result<Value, BjornError> {
+        if op == Token::AND || op == Token::OR {
+            // The right operand is only evaluated if the left one does not decide
+            let left = self.visit(left)?.boolean(&op)?;
+            if left == (op == Token::OR) {
+                return Ok(Value::Bool(left))
+            }
+            return Ok(Value::Bool(self.visit(right)?.boolean(&op)?))
+        }
+        let left = self.visit(left)?;
+        let right = self.visit(right)?;
+        binary_operation(left, &op, right)
+    }
+
+    fn comparison(&mut self, operands: Vec<AST>, operators: Vec<Token>) -> Result<Value, BjornError> {
+        // Each operand is evaluated once, and only if the previous comparisons hold
+        let mut operands = operands.into_iter();
+        let mut left = self.visit(operands.next().unwrap())?;
+        for (op, right) in operators.iter().zip(operands) {
+            let right = self.visit(right)?;
+            if binary_operation(left, op, right.clone())? != Value::Bool(true) {
+                return Ok(Value::Bool(false))
+            }
+            left = right;
+        }
+        Ok(Value::Bool(true))
+    }
+
+    fn unary_operation(&mut self, op: Token, right: AST) -> Result<Value, BjornError> {
+        if op == Token::PLUS {
+            self.visit(right)
+        } else if op == Token::MINUS {
+            let right: Value = self.visit(right)?;
+            -right
+        } else if op == Token::NOT {
+            let right: Value = self.visit(right)?;
+            !right
+        } else {
+            Err(BjornError::SyntaxError(format!("unknown unary operator {}", op).into()))
+        }
+    }
 
-                let mut hash_map_arguments = HashMap::new();
-                for (i, argument) in arguments.iter().enumerate() {
-                    let argument_value = self.visit(argument.clone());
-                    hash_map_arguments.insert(i.to_string(), argument_value);
+    fn function_call(&mut self, callee: AST, arguments: Vec<AST>, span: Span) -> Result<Value, BjornError> {
+        let callee = match callee {
+            AST::Variable {id, ..} => {
+                let function_name = id.identifier().unwrap();
+                match self.memory.get(function_name.clone()) {
+                    Some(value) => value,
+                    None => return Err(self.undefined(
+                        format!("function `{}` is not defined", function_name), &function_name
+                    )),
                 }
+            },
+            callee => self.visit(callee)?,
+        };
+        // An empty argument list is represented by a single `AST::Empty`.
+        let mut values = Vec::new();
+        for argument in arguments.into_iter().filter(|argument| *argument != AST::Empty) {
+            values.push(self.visit(argument)?);
+        }
+        self.call(callee, values, span)
+    }
+
+    fn variable(&self, id: Token) -> Result<Value, BjornError> {
+        let variable_name = id.identifier().unwrap();
+        match self.memory.get(variable_name.clone()) {
+            Some(variable_value) => Ok(variable_value),
+            None => Err(self.undefined(
+                format!("name `{}` is not defined", variable_name), &variable_name
+            )),
+        }
+    }
+
+    /// Evaluate the parts of an assignment target, so that it can be loaded and stored.
+    /// The parser only lets valid targets through.
+    fn target(&mut self, tree: AST) -> Result<Target, BjornError> {
+        match tree {
+            AST::Variable {id, ..} => Ok(Target::Variable(id.identifier().unwrap())),
+            AST::Index {value, index, ..} => {
+                let value = self.visit(*value)?;
+                let index = self.visit(*index)?;
+                Ok(Target::Item(value, index))
+            },
+            // The elements are evaluated as they are stored, once the values are known
+            AST::Tuple {elements, ..} => {
+                let starred = elements.iter().position(|element| matches!(element, AST::Starred {..}));
+                Ok(Target::Unpack {targets: elements, starred: starred})
+            },
+            _ => Err(BjornError::SyntaxError(
+                "cannot assign to an expression".into()
+            )),
+        }
+    }
+
+    fn load(&self, target: &Target) -> Result<Value, BjornError> {
+        match target {
+            Target::Variable(name) => match self.memory.get(name.clone()) {
+                Some(value) => Ok(value),
+                None => Err(self.undefined(format!("name `{}` is not defined", name), name)),
+            },
+            Target::Item(value, index) => value.index(index),
+            Target::Unpack {..} => Err(BjornError::SyntaxError(
+                "cannot use augmented assignment with several targets".into()
+            )),
+        }
+    }
 
-                self.memory.push_scope(hash_map_arguments);
-                self.load_functions(function_ast.clone());
-                self.load_builtins();
-                let function_result = self.visit(function_ast);
-                self.memory.pop_scope();
-                function_result
+    fn store(&mut self, target: Target, value: Value) -> Result<(), BjornError> {
+        match target {
+            Target::Variable(name) => {
+                self.memory.insert(name, value);
+                Ok(())
             },
-            AST::Variable {id} => {
-                let variable_name = id.identifier().unwrap();
-                let buf = self.memory.get(variable_name);
-                if let Some(variable_value) = buf {
-                    variable_value.clone()
-                } else {
-                    panic!("Interpreter error.")
+            Target::Item(container, index) => container.set_index(&index, value),
+            Target::Unpack {targets, starred} => {
+                let mut values: Vec<Value> = value.iter()?.collect();
+                let expected = targets.len() - starred.map_or(0, |_| 1);
+                if values.len() < expected || (starred.is_none() && values.len() > expected) {
+                    let at_least = if starred.is_some() { "at least " } else { "" };
+                    return Err(BjornError::ArityError(format!(
+                        "expected {}{} value(s) to unpack but got {}", at_least, expected, values.len()
+                    ).into()))
                 }
+                // The starred target takes what the targets after it leave
+                if let Some(i) = starred {
+                    let rest: Vec<Value> = values.drain(i..values.len() + i + 1 - targets.len()).collect();
+                    values.insert(i, Value::list(rest));
+                }
+                for (target, value) in targets.into_iter().zip(values) {
+                    let target = match target {
+                        AST::Starred {value, ..} => *value,
+                        target => target,
+                    };
+                    let target = self.target(target)?;
+                    self.store(target, value)?;
+                }
+                Ok(())
             },
-            _ => Value::None
         }
     }
 
-    pub fn interpret(&mut self) -> Value {
-        let tree = self.parser.parse();
-        self.load_functions(tree.clone());
+    /// Bound of a slice, `None` if omitted.
+    fn slice_bound(&mut self, tree: AST) -> Result<Option<i32>, BjornError> {
+        if tree == AST::Empty {
+            return Ok(None)
+        }
+        match self.visit(tree)? {
+            Value::Int(i) => Ok(Some(i)),
+            a => Err(BjornError::TypeError(
+                format!("slice bounds must be `int`, not `{}`", a.type_name()).into()
+            )),
+        }
+    }
+
+    /// Error for an undefined `name`, suggesting a close defined name if any.
+    fn undefined(&self, message: String, name: &str) -> BjornError {
+        let error = BjornError::NameError(message.into());
+        let names = self.memory.names();
+        match suggest(name, names.iter().map(|name| name.as_str())) {
+            Some(candidate) => error.with_help(&format!("did you mean `{}`?", candidate)),
+            None => error,
+        }
+    }
+
+    pub fn interpret(&mut self) -> Result<Value, BjornError> {
+        let (tree, errors) = self.parser.parse();
+        if let Some(error) = errors.into_iter().next() {
+            return Err(error)
+        }
         self.load_builtins();
+        self.load_functions(tree.clone())?;
         self.visit(tree)
     }
 }
+
+/// Error for a `global` or `nonlocal` declaration of a name already bound locally.
+fn declared_after_use(name: &str, keyword: &str) -> BjornError {
+    BjornError::SyntaxError(
+        format!("name `{}` is assigned to before {} declaration", name, keyword).into()
+    ).with_help(&format!("move the `{}` declaration to the start of the function", keyword))
+}
+
+/// Apply a binary operator to already evaluated operands.
+fn binary_operation(left: Value, op: &Token, right: Value) -> Result<Value, BjornError> {
+    if *op == Token::PLUS {
+        left + right
+    } else if *op == Token::MINUS {
+        left - right
+    } else if *op == Token::MUL {
+        left * right
+    } else if *op == Token::DIV {
+        left / right
+    } else if *op == Token::FLOORDIV {
+        left.floor_div(right)
+    } else if *op == Token::MOD {
+        left % right
+    } else if *op == Token::POW {
+        left.pow(right)
+    } else if *op == Token::IN {
+        Ok(Value::Bool(right.contains(&left)?))
+    } else if *op == Token::EQ {
+        Ok(Value::Bool(left.equals(&right)?))
+    } else if *op == Token::NE {
+        Ok(Value::Bool(!left.equals(&right)?))
+    } else if *op == Token::LE {
+        Ok(Value::Bool(left.compare(&right)? != Ordering::Greater))
+    } else if *op == Token::GE {
+        Ok(Value::Bool(left.compare(&right)? != Ordering::Less))
+    } else if *op == Token::LT {
+        Ok(Value::Bool(left.compare(&right)? == Ordering::Less))
+    } else if *op == Token::GT {
+        Ok(Value::Bool(left.compare(&right)? == Ordering::Greater))
+    } else {
+        Err(BjornError::SyntaxError(format!("unknown binary operator {}", op).into()))
+    }
+}
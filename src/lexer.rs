@@ -1,41 +1,162 @@
 use unicode_segmentation::UnicodeSegmentation;
 use unicode_segmentation::Graphemes;
+use unicode_xid::UnicodeXID;
+use unicode_normalization::UnicodeNormalization;
 use regex::Regex;
 
-use std::iter::Peekable;
-
 use token::Token;
+use span::Span;
+use error::BjornError;
 
 
 lazy_static! {
     static ref NUMERIC: Regex = Regex::new(r"^\d").unwrap();
-    static ref ALPHABETIC: Regex = Regex::new(r"^[a-zA-Z]").unwrap();
     static ref WORD: Regex = Regex::new(r"^\w+").unwrap();
+    static ref RADIX_PREFIX: Regex = Regex::new(r"^[xXoObB]$").unwrap();
+    static ref INTEGER: Regex = Regex::new(
+        r"^([0-9](_?[0-9])*|0[xX](_?[0-9a-fA-F])+|0[oO](_?[0-7])+|0[bB](_?[01])+)$"
+    ).unwrap();
+    static ref FLOAT: Regex = Regex::new(
+        r"^([0-9](_?[0-9])*\.([0-9](_?[0-9])*)?|\.[0-9](_?[0-9])*|[0-9](_?[0-9])*(\.)?)([eE][+-]?[0-9](_?[0-9])*)?$"
+    ).unwrap();
+}
+
+const BOM: &str = "\u{feff}";
+
+/// Identifiers follow UAX #31, with `_` allowed as a start character.
+fn is_id_start(grapheme: &str) -> bool {
+    let mut chars = grapheme.chars();
+    match chars.next() {
+        Some(c) if c == '_' || UnicodeXID::is_xid_start(c) => chars.all(UnicodeXID::is_xid_continue),
+        _ => false,
+    }
+}
+
+fn is_id_continue(grapheme: &str) -> bool {
+    grapheme.chars().all(UnicodeXID::is_xid_continue)
+}
+
+/// Graphemes of the input, with `\r\n` and `\r` line endings read as `\n`.
+struct Source<'a> {
+    graphemes: Graphemes<'a>,
+    // Next grapheme once peeked, along with its length in the input
+    peeked: Option<Option<(&'a str, usize)>>,
+}
+
+impl<'a> Source<'a> {
+
+    fn new(input: &'a str) -> Source<'a> {
+        Source {graphemes: UnicodeSegmentation::graphemes(input, true), peeked: None}
+    }
+
+    fn read(&mut self) -> Option<(&'a str, usize)> {
+        self.graphemes.next().map(|grapheme| match grapheme {
+            "\r\n" | "\r" => ("\n", grapheme.len()),
+            _ => (grapheme, grapheme.len()),
+        })
+    }
+
+    fn peek(&mut self) -> Option<&&'a str> {
+        if self.peeked.is_none() {
+            self.peeked = Some(self.read());
+        }
+        match self.peeked {
+            Some(Some((ref grapheme, _))) => Some(grapheme),
+            _ => None,
+        }
+    }
+
+    fn next(&mut self) -> Option<(&'a str, usize)> {
+        match self.peeked.take() {
+            Some(peeked) => peeked,
+            None => self.read(),
+        }
+    }
 }
 
 pub struct Lexer<'a> {
-    input: Peekable<Graphemes<'a>>,
-    indent_level: u8,
+    input: Source<'a>,
+    // Whitespace prefixes of the enclosing blocs, the innermost last
+    indents: Vec<String>,
+    // Number of open brackets, lines are joined inside them
+    depth: usize,
+    // Position of the next grapheme
+    offset: usize,
+    line: usize,
+    column: usize,
+    // Position of the first grapheme of the current lexeme
+    start: Span,
+    source: &'a str,
+    // End of the input covered by the lexemes so far, when preserving trivia
+    covered: Option<Span>,
 }
 
 impl<'a> Lexer<'a> {
 
     pub fn new(input: &'a str) -> Lexer<'a>  {
-        Lexer { input: UnicodeSegmentation::graphemes(input, true).peekable(),
-                indent_level: 0,
+        // A byte order mark is not part of the source code.
+        let bom = if input.starts_with(BOM) { BOM.len() } else { 0 };
+        Lexer { input: Source::new(&input[bom..]),
+                indents: Vec::new(),
+                depth: 0,
+                offset: bom,
+                line: 1,
+                column: 1,
+                start: Span::default(),
+                source: input,
+                covered: None,
             }
     }
 
-    fn advance(&mut self) -> &str {
-        match self.input.next() {
-            Some(c) => c,
-            None => panic!("Lexical error.")
+    /// Lexer also producing the whitespace and comments in between lexemes,
+    /// so that the lexemes cover the whole input.
+    /// Invalid input is produced as `ERROR` trivia, besides the error.
+    pub fn with_trivia(input: &'a str) -> Lexer<'a> {
+        Lexer {covered: Some(Span::default()), ..Lexer::new(input)}
+    }
+
+    fn advance(&mut self) -> &'a str {
+        // Only called once the next grapheme has been peeked.
+        self.bump().unwrap_or("")
+    }
+
+    fn bump(&mut self) -> Option<&'a str> {
+        let (c, len) = self.input.next()?;
+        self.offset += len;
+        if c == "\n" {
+            self.line += 1;
+            self.column = 1;
+        } else {
+            self.column += 1;
+        }
+        Some(c)
+    }
+
+    pub fn location(&self) -> Span {
+        Span::new(self.offset, self.offset, self.line, self.column)
+    }
+
+    fn span(&self) -> Span {
+        self.start.to(self.location())
+    }
+
+    fn lexeme(&self, token: Token) -> Option<Result<Vec<(Token, Span)>, BjornError>> {
+        Some(Ok(vec![(token, self.span())]))
+    }
+
+    /// Operator just read, or its augmented assignment if followed by `=`.
+    fn operator(&mut self, operator: Token, assignment: Token) -> Token {
+        if self.input.peek() == Some(&"=") {
+            self.advance();
+            assignment
+        } else {
+            operator
         }
     }
 
     fn whitespace (&mut self)  {
         while let Some(&c) = self.input.peek() {
-            if c != " " {
+            if c != " " && c != "\t" {
                 break;
             } else {
                 self.advance();
@@ -43,103 +164,267 @@ impl<'a> Lexer<'a> {
         }
     }
 
-    fn indent (&mut self) -> Option<Vec<Token>> {
-        // For now at least, identation is forced to 4 spaces
-        let spaces_for_indent = 4;
-
-        let mut spaces_count = 0;
+    fn indent (&mut self) -> Option<Result<Vec<(Token, Span)>, BjornError>> {
+        let newline = (Token::NEWLINE, self.span());
+        let mut prefix = String::new();
+        let mut line_start = self.location();
         while let Some(&c) = self.input.peek() {
-            if c != " " && c != "\n" {
-                if spaces_count % spaces_for_indent != 0 {
-                    panic!("Indentation error.")
-                }
-                let indent_count = spaces_count / spaces_for_indent;
-                let mut indent_array: Vec<Token> = vec![Token::NEWLINE];
-                if indent_count == self.indent_level {
-                    // Same level of indentation
-                    return Some(indent_array)
-                } else if indent_count > self.indent_level {
-                    // At least one additional identation
-                    for _ in 0..(indent_count - self.indent_level) {
-                        self.indent_level += 1;
-                        indent_array.push(Token::INDENT);
-                    }
-                    return Some(indent_array)
-                } else {
-                    // At least one indentation in less
-                    for _ in 0..(self.indent_level - indent_count) {
-                        self.indent_level -= 1;
-                        indent_array.push(Token::DEDENT);
-                    }
-                    return Some(indent_array)
-                }
+            if c == " " || c == "\t" {
+                prefix.push_str(self.advance());
             } else if c == "\n" {
-                spaces_count = 0;
+                // Blank lines do not change the indentation
+                prefix.clear();
                 self.advance();
+                line_start = self.location();
             } else {
-                spaces_count += 1;
-                self.advance();
+                let indentation = line_start.to(self.location());
+                return Some(self.indentation(prefix, indentation).map(|mut tokens| {
+                    tokens.insert(0, newline);
+                    tokens
+                }))
+            }
+        }
+        let mut ending = self.dedent_ending();
+        ending.insert(0, newline);
+        Some(Ok(ending))
+    }
+
+    /// In the end of the program,
+    /// dedent everything if the indent level is not equal to zero.
+    fn dedent_ending(&mut self) -> Vec<(Token, Span)> {
+        let ending = self.location();
+        self.indents.drain(..).map(|_| (Token::DEDENT, ending)).collect()
+    }
+
+    /// Compare the whitespace `prefix` of a new line to the enclosing blocs.
+    /// A bloc may be indented by any amount of whitespace,
+    /// as long as its lines share exactly the same prefix.
+    fn indentation(&mut self, prefix: String, span: Span) -> Result<Vec<(Token, Span)>, BjornError> {
+        let current = self.indents.last().cloned().unwrap_or_default();
+        if prefix == current {
+            // Same level of indentation
+            return Ok(Vec::new())
+        }
+        if prefix.starts_with(&current) {
+            // One additional indentation
+            self.indents.push(prefix);
+            return Ok(vec![(Token::INDENT, span)])
+        }
+        if prefix.is_empty() || self.indents.contains(&prefix) {
+            // Back to an enclosing level of indentation
+            let mut dedents = Vec::new();
+            while self.indents.last().is_some_and(|indent| *indent != prefix) {
+                self.indents.pop();
+                dedents.push((Token::DEDENT, span));
             }
+            return Ok(dedents)
         }
-        // In the end of the program,
-        // dedent everything if the indent level is not equal to zero.
-        let mut dedent_ending_array: Vec<Token> = vec![Token::NEWLINE];
-        for _ in 0..self.indent_level {
-            dedent_ending_array.push(Token::DEDENT);
+        if prefix.contains('\t') != current.contains('\t') || prefix.contains(' ') != current.contains(' ') {
+            return Err(BjornError::IndentationError(
+                "inconsistent use of tabs and spaces in indentation".into()
+            ).at(span).with_help("indent with either tabs or spaces, not both"))
         }
-        Some(dedent_ending_array)
+        Err(BjornError::IndentationError(
+            "dedent does not match any outer indentation level".into()
+        ).at(span).with_help("align the line with one of the enclosing blocs"))
     }
 
-    fn number(&mut self, number: &str) -> Option<Vec<Token>> {
+    fn number(&mut self, number: &str) -> Option<Result<Vec<(Token, Span)>, BjornError>> {
         let mut number = number.to_string();
+        let radix = number == "0" && match self.input.peek() {
+            Some(&c) => RADIX_PREFIX.is_match(c),
+            None => false,
+        };
         while let Some(&c) = self.input.peek() {
-            if c == "." {
+            let exponent = !radix && (number.ends_with('e') || number.ends_with('E'));
+            if WORD.is_match(c)
+            || (c == "." && !radix && !number.contains('.') && !number.contains(['e', 'E']))
+            || ((c == "+" || c == "-") && exponent) {
                 number.push_str(self.advance());
-                while let Some(&d) = self.input.peek() {
-                    if !NUMERIC.is_match(d) {
-                        break;
-                    }
-                    number.push_str(self.advance());
-                }
-                return Some(vec![Token::FLOAT(number)]);
-            }
-            if !NUMERIC.is_match(c) {
+            } else {
                 break;
             }
-            number.push_str(self.advance());
         }
-        Some(vec![Token::INT(number)])
+
+        let token = if INTEGER.is_match(&number) {
+            Token::INT(number.clone())
+        } else if FLOAT.is_match(&number) {
+            Token::FLOAT(number.clone())
+        } else {
+            return Some(Err(BjornError::LexicalError(
+                format!("invalid number `{}`", number).into()
+            ).at(self.span()).with_help(
+                "digits may only be separated by single underscores, \
+                 and must match the base of the number"
+            )))
+        };
+        let help = match token {
+            // The sign is not part of the literal, `-2147483648` cannot be written
+            Token::INT(_) if token.integer().is_none() => format!(
+                "integer literals must be at most {}", i32::MAX
+            ),
+            Token::FLOAT(_) if token.float().is_none() => format!(
+                "floats must be at most {:e}", f64::MAX
+            ),
+            _ => return self.lexeme(token),
+        };
+        Some(Err(BjornError::LexicalError(
+            format!("number `{}` is out of range", number).into()
+        ).at(self.span()).with_help(&help)))
     }
 
-    fn id(&mut self, id: &str) -> Option<Vec<Token>> {
+    fn id(&mut self, id: &str) -> Option<Result<Vec<(Token, Span)>, BjornError>> {
         let mut id = id.to_string();
         while let Some(&c) = self.input.peek() {
-            if !WORD.is_match(c) {
+            if !is_id_continue(c) {
                 break;
             }
             id.push_str(self.advance());
         }
+        // Identifiers written with different code points
+        // but rendered the same are the same identifier.
+        let id: String = id.nfc().collect();
         // Reserved keywords
-        match id.as_ref() {
-            "true" => Some(vec![Token::BOOL(true)]),
-            "false" => Some(vec![Token::BOOL(false)]),
+        let token = match id.as_ref() {
+            "true" => Token::BOOL(true),
+            "false" => Token::BOOL(false),
+
+            "or" => Token::OR,
+            "and" => Token::AND,
+            "not" => Token::NOT,
+
+            "if" => Token::IF,
+            "else" => Token::ELSE,
+            "while" => Token::WHILE,
+            "for" => Token::FOR,
+            "in" => Token::IN,
+            "break" => Token::BREAK,
+            "continue" => Token::CONTINUE,
+
+            "def" => Token::DEF,
+            "return" => Token::RETURN,
+            "lambda" => Token::LAMBDA,
+            "global" => Token::GLOBAL,
+            "nonlocal" => Token::NONLOCAL,
 
-            "or" => Some(vec![Token::OR]),
-            "and" => Some(vec![Token::AND]),
-            "not" => Some(vec![Token::NOT]),
+            _ => Token::ID(id)
+        };
+        self.lexeme(token)
+    }
 
-            "if" => Some(vec![Token::IF]),
-            "else" => Some(vec![Token::ELSE]),
-            "while" => Some(vec![Token::WHILE]),
+    fn string(&mut self, quote: &str, raw: bool) -> Option<Result<Vec<(Token, Span)>, BjornError>> {
+        // Two quotes are either an empty string or the start of a triple-quoted string.
+        let mut triple = false;
+        if self.input.peek() == Some(&quote) {
+            self.advance();
+            if self.input.peek() != Some(&quote) {
+                return self.lexeme(Token::STRING(String::new()));
+            }
+            self.advance();
+            triple = true;
+        }
+        let delimiter = if triple { quote.repeat(3) } else { quote.to_string() };
 
-            "def" => Some(vec![Token::DEF]),
-            "return" => Some(vec![Token::RETURN]),
+        let mut string = String::new();
+        // The first invalid escape is reported once the whole string is read.
+        let mut error = None;
+        loop {
+            let escape_start = self.location();
+            match self.input.peek().cloned() {
+                Some(c) if c == quote => {
+                    self.advance();
+                    if !triple {
+                        break;
+                    }
+                    if self.input.peek() != Some(&quote) {
+                        string.push_str(c);
+                        continue;
+                    }
+                    self.advance();
+                    if self.input.peek() != Some(&quote) {
+                        string.push_str(c);
+                        string.push_str(c);
+                        continue;
+                    }
+                    self.advance();
+                    break;
+                },
+                Some("\\") if !raw => {
+                    self.advance();
+                    match self.escape() {
+                        Ok(c) => string.push(c),
+                        Err(e) => if error.is_none() {
+                            error = Some(e.at(escape_start.to(self.location())));
+                        },
+                    }
+                },
+                Some("\n") if !triple => return Some(Err(self.unterminated_string(&delimiter))),
+                None => return Some(Err(self.unterminated_string(&delimiter))),
+                Some(_) => string.push_str(self.advance()),
+            }
+        }
+        match error {
+            Some(error) => Some(Err(error)),
+            None => self.lexeme(Token::STRING(string)),
+        }
+    }
 
-            _ => Some(vec![Token::ID(id)])
+    /// Character of the escape sequence following a backslash.
+    fn escape(&mut self) -> Result<char, BjornError> {
+        let c = match self.input.peek() {
+            Some(&"\n") | None => return Err(BjornError::LexicalError(
+                "unterminated escape sequence".into()
+            )),
+            Some(_) => self.advance(),
+        };
+        match c {
+            "n" => Ok('\n'),
+            "t" => Ok('\t'),
+            "r" => Ok('\r'),
+            "0" => Ok('\0'),
+            "\\" => Ok('\\'),
+            "\"" => Ok('"'),
+            "'" => Ok('\''),
+            "u" => {
+                if self.input.peek() != Some(&"{") {
+                    return Err(BjornError::LexicalError(
+                        "expected `{` after `\\u`".into()
+                    ).with_help("write unicode escapes as `\\u{1F600}`"));
+                }
+                self.advance();
+                let mut digits = String::new();
+                while let Some(&d) = self.input.peek() {
+                    if d == "}" || d == "\n" || d == "\"" || d == "'" {
+                        break;
+                    }
+                    digits.push_str(self.advance());
+                }
+                if self.input.peek() != Some(&"}") {
+                    return Err(BjornError::LexicalError(
+                        "unterminated unicode escape".into()
+                    ).with_help("close the unicode escape with `}`"));
+                }
+                self.advance();
+                u32::from_str_radix(&digits, 16).ok()
+                    .filter(|_| !digits.is_empty() && digits.len() <= 6)
+                    .and_then(::std::char::from_u32)
+                    .ok_or_else(|| BjornError::LexicalError(format!(
+                        "invalid unicode escape `\\u{{{}}}`", digits
+                    ).into()).with_help("use at most 6 hexadecimal digits of a valid code point"))
+            },
+            c => Err(BjornError::LexicalError(
+                format!("unknown escape sequence `\\{}`", c).into()
+            ).with_help("use `\\\\` for a literal backslash")),
         }
     }
 
-    fn comment (&mut self) -> Option<Vec<Token>> {
+    fn unterminated_string(&self, delimiter: &str) -> BjornError {
+        BjornError::LexicalError("unterminated string".into())
+            .at(self.span())
+            .with_help(&format!("close the string with `{}`", delimiter))
+    }
+
+    fn comment (&mut self) -> Option<Result<Vec<(Token, Span)>, BjornError>> {
         while let Some(&c) = self.input.peek() {
             if c == "\n" {
                 break;
@@ -147,71 +432,243 @@ impl<'a> Lexer<'a> {
                 self.advance();
             }
         }
-        self.next()
+        self.lex()
     }
-}
 
-impl<'a> Iterator for Lexer<'a> {
-    type Item = Vec<Token>;
+    /// Interleave the trivia found before each of the `lexemes`.
+    fn with_trivia_between(&mut self, lexemes: Vec<(Token, Span)>, mut covered: Span) -> Vec<(Token, Span)> {
+        let mut output = Vec::new();
+        for (token, span) in lexemes {
+            if span.start > covered.start {
+                output.extend(trivia(&self.source[covered.start..span.start], covered));
+                covered = span;
+            }
+            if span.end > covered.start {
+                covered = locate(&self.source[span.start..span.end], span);
+            }
+            output.push((token, span));
+        }
+        self.covered = Some(covered);
+        output
+    }
 
-    fn next(&mut self) -> Option<Self::Item> {
+    fn lex(&mut self) -> Option<Result<Vec<(Token, Span)>, BjornError>> {
 
+        let first_line = self.line == 1 && self.column == 1;
+        let line_start = self.location();
         self.whitespace();
+        // Nothing encloses the first line, it cannot be indented
+        if first_line && self.column > 1 && !matches!(self.input.peek(), Some(&"\n") | Some(&"#") | None) {
+            return Some(Err(unexpected_indent(line_start.to(self.location()))))
+        }
 
-         match self.input.next() {
-            Some(c) if NUMERIC.is_match(c) => self.number(c),
-            Some(c) if ALPHABETIC.is_match(c) => self.id(c),
-            Some("\n") => self.indent(),
+        self.start = self.location();
+        let token = match self.bump() {
+            Some(c) if NUMERIC.is_match(c) => return self.number(c),
+            Some(".") if self.input.peek().is_some_and(|c| NUMERIC.is_match(c)) => return self.number("."),
+            Some("r") if self.input.peek() == Some(&"\"") || self.input.peek() == Some(&"'") => {
+                let quote = self.advance();
+                return self.string(quote, true)
+            },
+            Some(c) if is_id_start(c) => return self.id(c),
+            Some("\n") if self.depth > 0 => return self.lex(),
+            Some("\n") => return self.indent(),
+            Some("\\") => {
+                if self.input.peek() == Some(&"\n") {
+                    // Explicit line joining
+                    let span = self.span();
+                    self.advance();
+                    self.whitespace();
+                    if self.input.peek().is_none() {
+                        return Some(Err(BjornError::LexicalError(
+                            "unexpected end of file after line continuation".into()
+                        ).at(span).with_help("continue the line after the `\\`, or remove it")))
+                    }
+                    return self.lex()
+                }
+                return Some(Err(BjornError::LexicalError(
+                    "unexpected character `\\`".into()
+                ).at(self.span()).with_help("a `\\` joining lines must be the last character of the line")))
+            },
+            Some("#") => return self.comment(),
+            Some(c) if c == "\"" || c == "'" => return self.string(c, false),
             Some("=") => {
                 if self.input.peek() == Some(&"=") {
                     self.advance();
-                    Some(vec![Token::EQ])
+                    Token::EQ
                 } else {
-                    Some(vec![Token::ASSIGN])
+                    Token::ASSIGN
                 }
 
             },
             Some("!") => {
                 if self.input.peek() == Some(&"=") {
                     self.advance();
-                    Some(vec![Token::NE])
+                    Token::NE
                 } else {
-                    panic!("Lexical error.") // Lexeme `!` is not supported
+                    // Lexeme `!` is not supported
+                    return Some(Err(BjornError::LexicalError(
+                        "unexpected character `!`".into()
+                    ).at(self.span()).with_help("use `not` for logical negation")))
                 }
             }
             Some("<") => {
                 if self.input.peek() == Some(&"=") {
                     self.advance();
-                    Some(vec![Token::LE])
+                    Token::LE
                 } else {
-                    Some(vec![Token::LT])
+                    Token::LT
                 }
             },
             Some(">") => {
                 if self.input.peek() == Some(&"=") {
                     self.advance();
-                    Some(vec![Token::GE])
+                    Token::GE
                 } else {
-                    Some(vec![Token::GT])
+                    Token::GT
                 }
             },
-            Some("+") => Some(vec![Token::PLUS]),
-            Some("-") => Some(vec![Token::MINUS]),
-            Some("*") => Some(vec![Token::MUL]),
-            Some("/") => Some(vec![Token::DIV]),
-            Some("(") => Some(vec![Token::LPAREN]),
-            Some(")") => Some(vec![Token::RPAREN]),
-            Some(":") => Some(vec![Token::COLON]),
-            Some(",") => Some(vec![Token::COMMA]),
-            Some("#") => self.comment(),
-
-            // End of file
-            None => None,
+            Some("+") => self.operator(Token::PLUS, Token::PLUSASSIGN),
+            Some("-") => self.operator(Token::MINUS, Token::MINUSASSIGN),
+            Some("*") => {
+                if self.input.peek() == Some(&"*") {
+                    self.advance();
+                    self.operator(Token::POW, Token::POWASSIGN)
+                } else {
+                    self.operator(Token::MUL, Token::MULASSIGN)
+                }
+            },
+            Some("/") => {
+                if self.input.peek() == Some(&"/") {
+                    self.advance();
+                    self.operator(Token::FLOORDIV, Token::FLOORDIVASSIGN)
+                } else {
+                    self.operator(Token::DIV, Token::DIVASSIGN)
+                }
+            },
+            Some("%") => self.operator(Token::MOD, Token::MODASSIGN),
+            Some("(") => {
+                self.depth += 1;
+                Token::LPAREN
+            },
+            Some(")") => {
+                self.depth = self.depth.saturating_sub(1);
+                Token::RPAREN
+            },
+            Some("[") => {
+                self.depth += 1;
+                Token::LBRACKET
+            },
+            Some("]") => {
+                self.depth = self.depth.saturating_sub(1);
+                Token::RBRACKET
+            },
+            Some("{") => {
+                self.depth += 1;
+                Token::LBRACE
+            },
+            Some("}") => {
+                self.depth = self.depth.saturating_sub(1);
+                Token::RBRACE
+            },
+            Some(".") => Token::DOT,
+            Some(":") => Token::COLON,
+            Some(",") => Token::COMMA,
+
+            // End of file, the last line may not end with a newline
+            None => {
+                if self.indents.is_empty() {
+                    return None
+                }
+                let mut ending = self.dedent_ending();
+                ending.insert(0, (Token::NEWLINE, self.location()));
+                return Some(Ok(ending))
+            },
 
             // Not supported lexeme
-            _ => panic!("Lexical error.")
+            Some(c) => return Some(Err(BjornError::LexicalError(
+                format!("unexpected character `{}`", c).into()
+            ).at(self.span())))
+        };
+        self.lexeme(token)
+    }
+}
+
+impl<'a> Iterator for Lexer<'a> {
+    type Item = Result<Vec<(Token, Span)>, BjornError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let covered = match self.covered {
+            Some(covered) => covered,
+            None => return self.lex(),
+        };
+        match self.lex() {
+            Some(Ok(lexemes)) => Some(Ok(self.with_trivia_between(lexemes, covered))),
+            Some(Err(error)) => Some(Err(error)),
+            None if covered.start < self.source.len() => {
+                // Trivia at the end of the input
+                self.covered = Some(self.location());
+                Some(Ok(trivia(&self.source[covered.start..], covered)))
+            },
+            None => None,
+        }
+    }
+}
+
+/// Error for the indentation `span` of a line not starting a bloc.
+pub fn unexpected_indent(span: Span) -> BjornError {
+    BjornError::IndentationError("unexpected indent".into())
+        .at(span)
+        .with_help("remove the extra indentation")
+}
+
+/// Location right after `text`, which starts at the location `from`.
+pub fn locate(text: &str, from: Span) -> Span {
+    let mut location = Span::new(from.start + text.len(), from.start + text.len(), from.line, from.column);
+    for (index, grapheme) in UnicodeSegmentation::grapheme_indices(text, true) {
+        match grapheme {
+            "\n" | "\r\n" | "\r" => {
+                location.line += 1;
+                location.column = 1;
+            },
+            // Like the lexer, do not count a byte order mark as a column
+            BOM if from.start + index == 0 => {},
+            _ => location.column += 1,
         }
     }
+    location
+}
+
+/// Split the text in between lexemes into whitespace, comments
+/// and invalid text the lexer reported an error for.
+fn trivia(text: &str, from: Span) -> Vec<(Token, Span)> {
+    let mut pieces: Vec<(Token, Span)> = Vec::new();
+    let mut location = from;
+    let mut rest = text;
+    // Backslashes joining lines are whitespace as well
+    let is_space = |c: char| c.is_whitespace() || c == '\\' || c == '\u{feff}';
+    while let Some(c) = rest.chars().next() {
+        let length = if c == '#' {
+            rest.find(['\n', '\r']).unwrap_or(rest.len())
+        } else if is_space(c) {
+            rest.find(|c| !is_space(c)).unwrap_or(rest.len())
+        } else {
+            rest.find(|c| is_space(c) || c == '#').unwrap_or(rest.len())
+        };
+        let (piece, remaining) = rest.split_at(length);
+        let token = if c == '#' {
+            Token::COMMENT(piece.to_string())
+        } else if is_space(c) {
+            Token::WHITESPACE(piece.to_string())
+        } else {
+            Token::ERROR(piece.to_string())
+        };
+        let end = locate(piece, location);
+        pieces.push((token, location.to(end)));
+        location = end;
+        rest = remaining;
+    }
+    pieces
 }
 
 
@@ -222,19 +679,91 @@ mod tests {
     use token::Token;
 
     fn scan_generator(input: &str) -> Vec<Token> {
+        try_scan_generator(input).unwrap()
+    }
+
+    fn try_scan_generator(input: &str) -> Result<Vec<Token>, BjornError> {
         let lexer = Lexer::new(input);
         let mut scan = Vec::new();
         for t in lexer {
-            scan.push(t);
+            scan.push(t?);
         }
-        scan.into_iter().flatten().collect::<Vec<Token>>()
+        Ok(scan.into_iter().flatten().map(|(token, _)| token).collect::<Vec<Token>>())
     }
 
     #[test]
-    #[should_panic]
     fn invalid_input() {
         // Must change if `§` is valid one day
-        scan_generator("§");
+        match try_scan_generator("§") {
+            Err(BjornError::LexicalError(..)) => {},
+            other => panic!("expected a lexical error, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn invalid_exclamation_mark() {
+        match try_scan_generator("!") {
+            Err(BjornError::LexicalError(..)) => {},
+            other => panic!("expected a lexical error, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn invalid_indentation() {
+        for input in &["a\n    b\n  c", "a\n    b\n\tc", "a\n\tb\n        c", "  a", "\ta\nb"] {
+            match try_scan_generator(input) {
+                Err(BjornError::IndentationError(..)) => {},
+                other => panic!("expected an indentation error for {:?}, got {:?}", input, other),
+            }
+        }
+        for input in &["  # comment\na", "  \na", "\u{feff}a"] {
+            assert!(try_scan_generator(input).is_ok(), "unexpected error for {:?}", input);
+        }
+    }
+
+    #[test]
+    fn inconsistent_indentation() {
+        let error = try_scan_generator("a\n    b\n\tc").unwrap_err();
+        assert_eq!(error.message(), "inconsistent use of tabs and spaces in indentation");
+        let error = try_scan_generator("a\n    b\n  c").unwrap_err();
+        assert_eq!(error.message(), "dedent does not match any outer indentation level");
+    }
+
+    #[test]
+    fn indentation_width() {
+        let expected = vec![
+            Token::ID(String::from("a")),
+            Token::NEWLINE,
+            Token::INDENT,
+            Token::ID(String::from("b")),
+            Token::NEWLINE,
+            Token::INDENT,
+            Token::ID(String::from("c")),
+            Token::NEWLINE,
+            Token::DEDENT,
+            Token::ID(String::from("d")),
+            Token::NEWLINE,
+            Token::DEDENT,
+            Token::ID(String::from("e")),
+        ];
+        for input in &["a\n  b\n     c\n  d\ne", "a\n\tb\n\t\tc\n\td\ne", "a\n\tb\n\t  c\n\td\ne"] {
+            assert_eq!(scan_generator(input), expected);
+        }
+    }
+
+    #[test]
+    fn indentation_blank_lines() {
+        let scan = scan_generator("a\n  b\n\n \t\n  c\n");
+        assert_eq!(scan, vec![
+            Token::ID(String::from("a")),
+            Token::NEWLINE,
+            Token::INDENT,
+            Token::ID(String::from("b")),
+            Token::NEWLINE,
+            Token::ID(String::from("c")),
+            Token::NEWLINE,
+            Token::DEDENT,
+            ])
     }
 
     #[test]
@@ -283,6 +812,76 @@ mod tests {
             ])
     }
 
+    #[test]
+    fn implicit_line_joining() {
+        let scan = scan_generator("f(a,\n    b,  # comment\n\n b)\nc");
+        assert_eq!(scan, vec![
+            Token::ID(String::from("f")),
+            Token::LPAREN,
+            Token::ID(String::from("a")),
+            Token::COMMA,
+            Token::ID(String::from("b")),
+            Token::COMMA,
+            Token::ID(String::from("b")),
+            Token::RPAREN,
+            Token::NEWLINE,
+            Token::ID(String::from("c")),
+        ]);
+    }
+
+    #[test]
+    fn explicit_line_joining() {
+        let scan = scan_generator("a = 1 + \\\n        2\nb");
+        assert_eq!(scan, vec![
+            Token::ID(String::from("a")),
+            Token::ASSIGN,
+            Token::INT(String::from("1")),
+            Token::PLUS,
+            Token::INT(String::from("2")),
+            Token::NEWLINE,
+            Token::ID(String::from("b")),
+        ]);
+    }
+
+    #[test]
+    fn line_joining_at_end_of_file() {
+        for input in &["x = 1 \\\n", "x = 1 \\\n    "] {
+            match try_scan_generator(input) {
+                Err(error @ BjornError::LexicalError(..)) => {
+                    assert_eq!(error.message(), "unexpected end of file after line continuation");
+                },
+                other => panic!("expected a lexical error for {:?}, got {:?}", input, other),
+            }
+        }
+    }
+
+    #[test]
+    fn invalid_backslash() {
+        for input in &["a \\ b", "a \\"] {
+            match try_scan_generator(input) {
+                Err(BjornError::LexicalError(..)) => {},
+                other => panic!("expected a lexical error for {:?}, got {:?}", input, other),
+            }
+        }
+    }
+
+    #[test]
+    fn trivia() {
+        let input = "a = 1  # one\n\nif a:\n    b\n";
+        let lexemes: Vec<(Token, Span)> = Lexer::with_trivia(input)
+            .flat_map(|lexeme| lexeme.unwrap())
+            .collect();
+        assert_eq!(lexemes[5], (Token::WHITESPACE(String::from("  ")), Span::new(5, 7, 1, 6)));
+        assert_eq!(lexemes[6], (Token::COMMENT(String::from("# one")), Span::new(7, 12, 1, 8)));
+        assert_eq!(lexemes[8], (Token::WHITESPACE(String::from("\n")), Span::new(13, 14, 2, 1)));
+        // Without trivia, the lexemes are the same
+        let tokens: Vec<Token> = lexemes.into_iter()
+            .map(|(token, _)| token)
+            .filter(|token| !token.is_trivia())
+            .collect();
+        assert_eq!(tokens, scan_generator(input));
+    }
+
     #[test]
     fn comment() {
         let scan = scan_generator("# 2+2");
@@ -301,6 +900,115 @@ mod tests {
         assert_eq!(scan, vec!(Token::FLOAT(String::from("1.0"))));
     }
 
+    #[test]
+    fn radix_integers() {
+        let scan = scan_generator("0xFF 0o17 0B1010 0x_7fff_FFFF");
+        assert_eq!(scan, vec![
+            Token::INT(String::from("0xFF")),
+            Token::INT(String::from("0o17")),
+            Token::INT(String::from("0B1010")),
+            Token::INT(String::from("0x_7fff_FFFF")),
+        ]);
+        let values: Vec<Option<i32>> = scan.iter().map(|token| token.integer()).collect();
+        assert_eq!(values, vec![Some(255), Some(15), Some(10), Some(i32::MAX)]);
+    }
+
+    #[test]
+    fn underscores_in_numbers() {
+        let scan = scan_generator("1_000_000 1_0.0_1");
+        assert_eq!(scan[0].integer(), Some(1000000));
+        assert_eq!(scan[1].float(), Some(10.01));
+    }
+
+    #[test]
+    fn float_exponents() {
+        let scan = scan_generator("1e-9 6.02E23 1.e3 2E+2");
+        let values: Vec<Option<f64>> = scan.iter().map(|token| token.float()).collect();
+        assert_eq!(values, vec![Some(1e-9), Some(6.02e23), Some(1e3), Some(2e2)]);
+    }
+
+    #[test]
+    fn leading_dot_float() {
+        let scan = scan_generator(".5 + 1.");
+        assert_eq!(scan, vec![
+            Token::FLOAT(String::from(".5")),
+            Token::PLUS,
+            Token::FLOAT(String::from("1.")),
+        ]);
+    }
+
+    #[test]
+    fn invalid_numbers() {
+        for input in &["0x", "0b102", "0o8", "1__0", "1_", "1_.5", "1e", "1e+", "1if", "2147483648", "0x80000000", "1e400"] {
+            match try_scan_generator(input) {
+                Err(BjornError::LexicalError(..)) => {},
+                other => panic!("expected a lexical error for `{}`, got {:?}", input, other),
+            }
+        }
+    }
+
+    #[test]
+    fn strings() {
+        let scan = scan_generator("\"abc\" 'd\"e' \"\" ''");
+        assert_eq!(scan, vec![
+            Token::STRING(String::from("abc")),
+            Token::STRING(String::from("d\"e")),
+            Token::STRING(String::new()),
+            Token::STRING(String::new()),
+        ]);
+    }
+
+    #[test]
+    fn string_escapes() {
+        let scan = scan_generator(r#""\n\t\"\'\\\u{e9}\u{1F600}""#);
+        assert_eq!(scan, vec!(Token::STRING(String::from("\n\t\"'\\é\u{1F600}"))));
+    }
+
+    #[test]
+    fn raw_strings() {
+        let scan = scan_generator(r#"r"a\nb" r'\'"#);
+        assert_eq!(scan, vec![
+            Token::STRING(String::from(r"a\nb")),
+            Token::STRING(String::from(r"\")),
+        ]);
+    }
+
+    #[test]
+    fn triple_quoted_strings() {
+        let scan = scan_generator("\"\"\"a\n\"b\"\"\n\"\"\" '''c'''");
+        assert_eq!(scan, vec![
+            Token::STRING(String::from("a\n\"b\"\"\n")),
+            Token::STRING(String::from("c")),
+        ]);
+    }
+
+    #[test]
+    fn invalid_strings() {
+        for input in &["\"abc", "'abc\n'", "\"\\q\"", "\"\\u{110000}\"", "\"\\u{41\"", "\"\"\"abc\"\""] {
+            match try_scan_generator(input) {
+                Err(BjornError::LexicalError(..)) => {},
+                other => panic!("expected a lexical error for {:?}, got {:?}", input, other),
+            }
+        }
+    }
+
+    #[test]
+    fn unterminated_string_help() {
+        for (input, help) in &[("'abc", "close the string with `'`"), ("\"\"\"abc\"", "close the string with `\"\"\"`")] {
+            match try_scan_generator(input) {
+                Err(error) => assert_eq!(error.help(), Some(*help)),
+                other => panic!("expected a lexical error for {:?}, got {:?}", input, other),
+            }
+        }
+    }
+
+    #[test]
+    fn string_span() {
+        let lexer = Lexer::new("a = 'bjørn\\n'");
+        let spans: Vec<Span> = lexer.flat_map(|lexeme| lexeme.unwrap()).map(|(_, span)| span).collect();
+        assert_eq!(spans[2], Span::new(4, 14, 1, 5));
+    }
+
     #[test]
     fn plus_operand() {
         let scan = scan_generator("+");
@@ -325,6 +1033,50 @@ mod tests {
         assert_eq!(scan, vec!(Token::DIV));
     }
 
+    #[test]
+    fn floordiv_operand() {
+        let scan = scan_generator("//");
+        assert_eq!(scan, vec!(Token::FLOORDIV));
+    }
+
+    #[test]
+    fn mod_operand() {
+        let scan = scan_generator("%");
+        assert_eq!(scan, vec!(Token::MOD));
+    }
+
+    #[test]
+    fn pow_operand() {
+        let scan = scan_generator("** * ***");
+        assert_eq!(scan, vec!(Token::POW, Token::MUL, Token::POW, Token::MUL));
+    }
+
+    #[test]
+    fn brackets() {
+        let scan = scan_generator("[1,\n 2]");
+        assert_eq!(scan, vec!(
+            Token::LBRACKET,
+            Token::INT(String::from("1")),
+            Token::COMMA,
+            Token::INT(String::from("2")),
+            Token::RBRACKET,
+        ));
+    }
+
+    #[test]
+    fn braces() {
+        let scan = scan_generator("{a:\n .5}.b");
+        assert_eq!(scan, vec!(
+            Token::LBRACE,
+            Token::ID(String::from("a")),
+            Token::COLON,
+            Token::FLOAT(String::from(".5")),
+            Token::RBRACE,
+            Token::DOT,
+            Token::ID(String::from("b")),
+        ));
+    }
+
     #[test]
     fn parenthesis() {
         let scan = scan_generator("(1)");
@@ -353,6 +1105,22 @@ mod tests {
         assert_eq!(scan, vec!(Token::ASSIGN));
     }
 
+    #[test]
+    fn augmented_assign() {
+        let scan = scan_generator("+= -= *= /= //= %= **= + =");
+        assert_eq!(scan, vec!(
+            Token::PLUSASSIGN,
+            Token::MINUSASSIGN,
+            Token::MULASSIGN,
+            Token::DIVASSIGN,
+            Token::FLOORDIVASSIGN,
+            Token::MODASSIGN,
+            Token::POWASSIGN,
+            Token::PLUS,
+            Token::ASSIGN,
+        ));
+    }
+
     #[test]
     fn boolean_true() {
         let scan = scan_generator("true");
@@ -371,6 +1139,33 @@ mod tests {
         assert_eq!(scan, vec!(Token::ID(String::from("bjørn"))));
     }
 
+    #[test]
+    fn unicode_identifiers() {
+        let scan = scan_generator("ørn π_2 _private Δx 変数");
+        assert_eq!(scan, vec![
+            Token::ID(String::from("ørn")),
+            Token::ID(String::from("π_2")),
+            Token::ID(String::from("_private")),
+            Token::ID(String::from("Δx")),
+            Token::ID(String::from("変数")),
+        ]);
+    }
+
+    #[test]
+    fn normalized_identifiers() {
+        // `e` followed by a combining acute accent is the same identifier as `é`
+        let scan = scan_generator("caf\u{65}\u{301} café");
+        assert_eq!(scan, vec![Token::ID(String::from("café")), Token::ID(String::from("café"))]);
+    }
+
+    #[test]
+    fn invalid_identifier_start() {
+        match try_scan_generator("∞") {
+            Err(BjornError::LexicalError(..)) => {},
+            other => panic!("expected a lexical error, got {:?}", other),
+        }
+    }
+
     #[test]
     fn comparison_eq() {
         let scan = scan_generator("==");
@@ -444,6 +1239,18 @@ mod tests {
         assert_eq!(scan, vec!(Token::WHILE));
     }
 
+    #[test]
+    fn for_keywords() {
+        let scan = scan_generator("for in");
+        assert_eq!(scan, vec!(Token::FOR, Token::IN));
+    }
+
+    #[test]
+    fn loop_control_keywords() {
+        let scan = scan_generator("break continue");
+        assert_eq!(scan, vec!(Token::BREAK, Token::CONTINUE));
+    }
+
     #[test]
     fn def_keyword() {
         let scan = scan_generator("def");
@@ -455,4 +1262,42 @@ mod tests {
         let scan = scan_generator("return");
         assert_eq!(scan, vec!(Token::RETURN));
     }
+
+    #[test]
+    fn lambda_keyword() {
+        let scan = scan_generator("lambda");
+        assert_eq!(scan, vec!(Token::LAMBDA));
+    }
+
+    #[test]
+    fn declaration_keywords() {
+        let scan = scan_generator("global nonlocal");
+        assert_eq!(scan, vec!(Token::GLOBAL, Token::NONLOCAL));
+    }
+
+    #[test]
+    fn spans() {
+        let scan = Lexer::new("ab + 1.5").flatten().flatten().collect::<Vec<(Token, Span)>>();
+        assert_eq!(scan, vec![
+            (Token::ID(String::from("ab")), Span::new(0, 2, 1, 1)),
+            (Token::PLUS, Span::new(3, 4, 1, 4)),
+            (Token::FLOAT(String::from("1.5")), Span::new(5, 8, 1, 6)),
+            ])
+    }
+
+    #[test]
+    fn spans_multiline() {
+        let scan = Lexer::new("a\n\n    bjørn = 2\nc").flatten().flatten().collect::<Vec<(Token, Span)>>();
+        assert_eq!(scan, vec![
+            (Token::ID(String::from("a")), Span::new(0, 1, 1, 1)),
+            (Token::NEWLINE, Span::new(1, 2, 1, 2)),
+            (Token::INDENT, Span::new(3, 7, 3, 1)),
+            (Token::ID(String::from("bjørn")), Span::new(7, 13, 3, 5)),
+            (Token::ASSIGN, Span::new(14, 15, 3, 11)),
+            (Token::INT(String::from("2")), Span::new(16, 17, 3, 13)),
+            (Token::NEWLINE, Span::new(17, 18, 3, 14)),
+            (Token::DEDENT, Span::new(18, 18, 4, 1)),
+            (Token::ID(String::from("c")), Span::new(18, 19, 4, 1)),
+            ])
+    }
 }
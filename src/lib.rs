@@ -2,25 +2,46 @@
 //! Interpreter for `bjørn` language.
 //!
 
+#![allow(clippy::upper_case_acronyms, clippy::redundant_field_names, clippy::vec_box)]
+
 #[macro_use] extern crate lazy_static;
 extern crate unicode_segmentation;
+extern crate unicode_xid;
+extern crate unicode_normalization;
+extern crate unicode_security;
 extern crate regex;
 
+use std::panic;
+use std::thread;
+
 mod token;
+mod span;
 mod lexer;
 mod parser;
 mod ast;
 pub mod memory;
 mod interpreter;
 mod value;
+mod dict;
+mod error;
+mod suggestion;
+mod lint;
 pub mod builtins;
+pub mod diagnostic;
+pub mod cst;
 
 use lexer::Lexer;
 use parser::Parser;
-use interpreter::Interpreter;
+use interpreter::{Interpreter, STACK_SIZE};
+
+pub use error::BjornError;
+pub use error::BjornWarning;
+pub use error::Frame;
+pub use span::Span;
 
 ///
 /// Only proceed to the lexical analysis.
+/// Each token comes with its location in the input.
 /// For testing purposes.
 ///
 /// ```
@@ -30,17 +51,19 @@ use interpreter::Interpreter;
 /// println!("{:?}", bjorn::scan(input));
 /// ```
 ///
-pub fn scan(input: &str) -> Vec<token::Token> {
+pub fn scan(input: &str) -> Result<Vec<(token::Token, Span)>, BjornError> {
     let lexer = Lexer::new(input);
     let mut scan = Vec::new();
     for t in lexer {
-        scan.push(t);
+        scan.push(t?);
     }
-    scan.into_iter().flatten().collect::<Vec<token::Token>>()
+    Ok(scan.into_iter().flatten().collect::<Vec<(token::Token, Span)>>())
 }
 
 ///
 /// Only proceed to the lexical and syntaxic analysis.
+/// The tree is built even if the input contains errors,
+/// with `Error` nodes in place of the invalid statements.
 /// For testing purposes.
 ///
 /// ```
@@ -50,10 +73,48 @@ pub fn scan(input: &str) -> Vec<token::Token> {
 /// println!("{:?}", bjorn::parse(input));
 /// ```
 ///
-pub fn parse(input: &str) -> ast::AST {
-    Parser::new(
+pub fn parse(input: &str) -> (ast::AST, Vec<BjornError>) {
+    let parse = || Parser::new(
         Lexer::new(input)
-    ).parse()
+    ).parse();
+    match on_large_stack(parse) {
+        Ok(parse) => parse,
+        Err(error) => (ast::AST::Error {span: Span::default()}, vec![error]),
+    }
+}
+
+///
+/// Report every lexical and syntaxic error of the input without running it.
+///
+/// ```
+/// extern crate bjorn;
+///
+/// let input = "if true\n    a = )\n";
+/// assert_eq!(bjorn::check(input).len(), 2);
+/// ```
+///
+pub fn check(input: &str) -> Vec<BjornError> {
+    parse(input).1
+}
+
+///
+/// Report the suspicious identifiers of the input,
+/// such as identifiers mixing scripts or looking like another one.
+///
+/// ```
+/// extern crate bjorn;
+///
+/// // The second `a` is a cyrillic letter.
+/// let input = "a = 1\nа = 2\n";
+/// assert_eq!(bjorn::lint(input).len(), 1);
+/// ```
+///
+pub fn lint(input: &str) -> Vec<BjornWarning> {
+    let tokens = Lexer::new(input)
+        .filter_map(|lexeme| lexeme.ok())
+        .flatten()
+        .collect::<Vec<(token::Token, Span)>>();
+    lint::identifiers(&tokens)
 }
 
 ///
@@ -63,15 +124,31 @@ pub fn parse(input: &str) -> ast::AST {
 /// extern crate bjorn;
 ///
 /// let input = "2 + 2";
-/// println!("{}", bjorn::interpret(input));
+/// println!("{}", bjorn::interpret(input).unwrap());
 /// ```
 ///
-pub fn interpret(input: &str) -> String {
-    Interpreter::new(
-        Parser::new(
-            Lexer::new(input)
-        )
-    ).interpret().to_string()
+pub fn interpret(input: &str) -> Result<String, BjornError> {
+    on_large_stack(|| {
+        Ok(Interpreter::new(
+            Parser::new(
+                Lexer::new(input)
+            )
+        ).interpret()?.to_string())
+    })?
+}
+
+/// Run `run` on its own thread, so that the depth of the nested expressions
+/// and calls it handles does not depend on the stack left to the caller.
+fn on_large_stack<T: Send, F: FnOnce() -> T + Send>(run: F) -> Result<T, BjornError> {
+    thread::scope(|scope| {
+        let handle = thread::Builder::new()
+            .stack_size(STACK_SIZE)
+            .spawn_scoped(scope, run)
+            .map_err(|error| BjornError::RuntimeError(
+                format!("cannot start the interpreter thread: {}", error).into()
+            ))?;
+        Ok(handle.join().unwrap_or_else(|payload| panic::resume_unwind(payload)))
+    })
 }
 
 
@@ -79,8 +156,43 @@ pub fn interpret(input: &str) -> String {
 mod tests {
     use super::*;
 
+    use std::rc::Rc;
+
     #[test]
     fn library_entrypoint() {
-        assert_eq!(interpret(""), "")
+        assert_eq!(interpret(""), Ok(String::from("")))
+    }
+
+    #[test]
+    fn library_entrypoint_error() {
+        match interpret("a") {
+            Err(BjornError::NameError(..)) => {},
+            other => panic!("expected a name error, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn deeply_nested_input() {
+        let nested = |open: &str, close: &str, depth: usize| {
+            format!("x = {}1{}\n", open.repeat(depth), close.repeat(depth))
+        };
+        assert!(check(&nested("(", ")", 900)).is_empty());
+        assert!(check(&nested("[", "]", 900)).is_empty());
+        assert_eq!(interpret(&format!("{}x", nested("[", "]", 900))).map(|x| x.len()), Ok(1801));
+        for (open, close) in &[("(", ")"), ("[", "]"), ("-", ""), ("not ", "")] {
+            let errors = check(&nested(open, close, 5000));
+            assert_eq!(errors.len(), 1);
+            assert_eq!(errors[0].message(), "too deeply nested");
+        }
+    }
+
+    #[test]
+    fn scopes_freed_after_interpret() {
+        let input = "xs = [1]\ndef f():\n    return xs\ndef make():\n    n = 0\n    def inc():\n        return n\n    return inc\ng = make()\n";
+        let mut interpreter = Interpreter::new(Parser::new(Lexer::new(input)));
+        interpreter.interpret().unwrap();
+        let global = interpreter.memory.environment();
+        drop(interpreter);
+        assert_eq!(Rc::strong_count(&global), 1);
     }
 }
@@ -0,0 +1,97 @@
+//!
+//! Warnings about identifiers that look alike but differ,
+//! following the Unicode security mechanisms (UTS #39).
+//!
+
+use std::collections::{HashMap, HashSet};
+
+use unicode_security::{skeleton, MixedScript, is_potential_mixed_script_confusable_char};
+
+use token::Token;
+use span::Span;
+use error::{BjornWarning, Diagnostic};
+
+
+fn warning(message: String, span: Span, help: String) -> Diagnostic {
+    Diagnostic {message: message, span: Some(span), help: Some(help), traceback: Vec::new()}
+}
+
+/// Lint the identifiers of a token stream.
+pub fn identifiers(tokens: &[(Token, Span)]) -> Vec<BjornWarning> {
+    let mut warnings = Vec::new();
+    // First occurrence of each identifier by skeleton
+    let mut skeletons: HashMap<String, (&str, Span)> = HashMap::new();
+    // Identifiers are only linted at their first occurrence
+    let mut seen = HashSet::new();
+
+    for (token, span) in tokens {
+        let id = match token {
+            Token::ID(id) if seen.insert(id.as_str()) => id.as_str(),
+            _ => continue,
+        };
+
+        // Non ASCII letters mixed with ASCII ones are a mistake
+        // when they all look like ASCII letters, rather than a notation such as `Δx`.
+        if !id.is_single_script()
+        && id.chars().filter(|c| !c.is_ascii()).all(is_potential_mixed_script_confusable_char) {
+            warnings.push(BjornWarning::MixedScriptIdentifier(warning(
+                format!("identifier `{}` mixes characters of different scripts", id),
+                *span,
+                String::from("use characters of a single script"),
+            )));
+        }
+
+        match skeletons.get(&skeleton(id).collect::<String>()) {
+            Some((other, other_span)) => {
+                warnings.push(BjornWarning::ConfusableIdentifier(warning(
+                    format!("identifier `{}` looks like `{}`", id, other),
+                    *span,
+                    format!("`{}` is used at {}, make sure they are different on purpose", other, other_span),
+                )));
+            },
+            None => {
+                skeletons.insert(skeleton(id).collect(), (id, *span));
+            },
+        }
+    }
+    warnings
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use lexer::Lexer;
+
+    fn lint(input: &str) -> Vec<BjornWarning> {
+        let tokens: Vec<(Token, Span)> = Lexer::new(input)
+            .flat_map(|lexeme| lexeme.unwrap())
+            .collect();
+        identifiers(&tokens)
+    }
+
+    #[test]
+    fn valid_identifiers() {
+        assert_eq!(lint("bjørn = 1\nπ = 3.14\nΔx = 1\nörn = bjørn + π + Δx\n"), vec![]);
+    }
+
+    #[test]
+    fn mixed_script_identifier() {
+        // `р` is a cyrillic letter
+        let warnings = lint("раssword = 1\n");
+        assert_eq!(warnings.len(), 1);
+        assert_eq!(warnings[0].code(), "W0001");
+        assert_eq!(warnings[0].span(), Some(Span::new(0, 10, 1, 1)));
+    }
+
+    #[test]
+    fn confusable_identifiers() {
+        // The second `a` is a cyrillic letter
+        let warnings = lint("a = 1\nа = 2\na + а\n");
+        assert_eq!(warnings.len(), 1);
+        assert_eq!(warnings[0].code(), "W0002");
+        assert_eq!(warnings[0].message(), "identifier `а` looks like `a`");
+        assert_eq!(warnings[0].span().map(|span| span.line), Some(2));
+    }
+}
@@ -3,6 +3,8 @@ extern crate clap;
 
 use std::fs;
 use std::io;
+use std::io::IsTerminal;
+use std::process;
 use clap::*;
 
 fn main() -> io::Result<()> {
@@ -15,10 +17,52 @@ fn main() -> io::Result<()> {
                  .takes_value(true)
                  .index(1)
                  .help("File path of the source code to interpret."))
+        .arg(Arg::with_name("error-format")
+                 .long("error-format")
+                 .takes_value(true)
+                 .possible_values(&["human", "json"])
+                 .default_value("human")
+                 .help("Format of the reported errors."))
+        .arg(Arg::with_name("check")
+                 .long("check")
+                 .help("Only report the syntax errors, without interpreting."))
         .get_matches();
 
-    let input = fs::read_to_string(matches.value_of("FILEPATH").unwrap())?;
+    let filepath = matches.value_of("FILEPATH").unwrap();
+    let input = fs::read_to_string(filepath)?;
+    let report = |error: &bjorn::BjornError| {
+        if matches.value_of("error-format") == Some("json") {
+            eprintln!("{}", bjorn::diagnostic::render_json(error, filepath));
+        } else {
+            let color = io::stderr().is_terminal();
+            eprint!("{}", bjorn::diagnostic::render(error, filepath, &input, color));
+        }
+    };
+
+    for warning in bjorn::lint(&input) {
+        if matches.value_of("error-format") == Some("json") {
+            eprintln!("{}", bjorn::diagnostic::render_warning_json(&warning, filepath));
+        } else {
+            let color = io::stderr().is_terminal();
+            eprint!("{}", bjorn::diagnostic::render_warning(&warning, filepath, &input, color));
+        }
+    }
+
+    // Report all the syntax errors at once before running anything.
+    let errors = bjorn::check(&input);
+    for error in &errors {
+        report(error);
+    }
+    if !errors.is_empty() {
+        process::exit(1);
+    }
+    if matches.is_present("check") {
+        return Ok(());
+    }
 
-    bjorn::interpret(&input);
+    if let Err(error) = bjorn::interpret(&input) {
+        report(&error);
+        process::exit(1);
+    }
     Ok(())
 }
@@ -1,44 +1,190 @@
-use std::collections::HashMap;
+use std::cell::{RefCell, RefMut};
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+use std::rc::{Rc, Weak};
 
 use value::Value;
 
 
+/// Scope shared between the memory and the functions defined in it.
+pub type Environment = Rc<RefCell<Scope>>;
+
+#[derive(Default)]
+pub struct Scope {
+    variables: HashMap<String, Value>,
+    /// Enclosing lexical scope, `None` for the builtins.
+    parent: Option<Environment>,
+    /// Names declared `global`, assigned in the global scope.
+    globals: HashSet<String>,
+    /// Names declared `nonlocal`, assigned in the enclosing scope defining them.
+    nonlocals: HashSet<String>,
+}
+
+impl fmt::Debug for Scope {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        // Functions refer to the scope they are defined in, only the names are shown
+        f.debug_set().entries(self.variables.keys()).finish()
+    }
+}
+
+impl Scope {
+    fn child(parent: &Environment, variables: HashMap<String, Value>) -> Environment {
+        Rc::new(RefCell::new(Scope {variables: variables, parent: Some(parent.clone()), ..Scope::default()}))
+    }
+}
+
+
+/// Scopes of the function calls being executed, each one looking up names
+/// in its enclosing scopes up to the global scope and then the builtins.
 #[derive(Debug)]
 pub struct Memory {
-    stack: Vec<HashMap<String, Value>>,
+    builtins: Environment,
+    global: Environment,
+    stack: Vec<Environment>,
+    /// Every scope created, the functions defined in a scope refer to it
+    /// so the variables are cleared on drop to break the reference cycles.
+    scopes: Vec<Weak<RefCell<Scope>>>,
 }
 
 
 impl Memory {
     pub fn new(init: HashMap<String, Value>) -> Memory {
-        Memory {stack: vec![init]}
+        let builtins = Rc::new(RefCell::new(Scope::default()));
+        let global = Scope::child(&builtins, init);
+        let scopes = vec![Rc::downgrade(&builtins), Rc::downgrade(&global)];
+        Memory {builtins: builtins, global: global.clone(), stack: vec![global], scopes: scopes}
+    }
+
+    fn current(&self) -> &Environment {
+        self.stack.last().unwrap()
     }
 
-    pub fn get(&self, key: String) -> Option<&Value> {
-        self.stack.last().unwrap().get(&key)
+    /// Value of `key`, looked up from the scope assignments to it bind it in.
+    pub fn get(&self, key: String) -> Option<Value> {
+        let mut scope = self.target(&key);
+        loop {
+            if let Some(value) = scope.borrow().variables.get(&key) {
+                return Some(value.clone())
+            }
+            let parent = scope.borrow().parent.clone()?;
+            scope = parent;
+        }
     }
 
     pub fn insert(&mut self, key: String, value: Value) -> Option<Value> {
-        self.stack.last_mut().unwrap().insert(key, value)
+        let scope = self.target(&key);
+        let previous = scope.borrow_mut().variables.insert(key, value);
+        previous
+    }
+
+    /// Scope an assignment to `key` binds the name in, and reads start from.
+    fn target(&self, key: &str) -> Environment {
+        let current = self.current().borrow();
+        if current.globals.contains(key) {
+            return self.global.clone()
+        }
+        if current.nonlocals.contains(key) {
+            if let Some(scope) = self.enclosing(key) {
+                return scope
+            }
+        }
+        self.current().clone()
+    }
+
+    /// Innermost scope defining `key` between the current and the global scope, both excluded.
+    fn enclosing(&self, key: &str) -> Option<Environment> {
+        let mut scope = self.current().borrow().parent.clone()?;
+        while !Rc::ptr_eq(&scope, &self.global) {
+            if scope.borrow().variables.contains_key(key) {
+                return Some(scope)
+            }
+            let parent = scope.borrow().parent.clone()?;
+            scope = parent;
+        }
+        None
     }
 
     pub fn remove(&mut self, key: String) -> Option<Value> {
-        self.stack.last_mut().unwrap().remove(&key)
+        self.current().borrow_mut().variables.remove(&key)
     }
 
-    pub fn push_scope(&mut self, init: HashMap<String, Value>) {
-        self.stack.push(init)
+    /// Scope assignments currently happen in, for the functions defined there.
+    pub fn environment(&self) -> Environment {
+        self.current().clone()
     }
 
-    pub fn pop_scope(&mut self) -> Option<HashMap<String, Value>> {
+    /// New scope for a function call, enclosed by the scope the function was defined in.
+    pub fn push_scope(&mut self, init: HashMap<String, Value>, parent: &Environment) {
+        let scope = Scope::child(parent, init);
+        if self.scopes.len() == self.scopes.capacity() {
+            // Forget the scopes already freed before growing
+            self.scopes.retain(|scope| scope.strong_count() > 0);
+        }
+        self.scopes.push(Rc::downgrade(&scope));
+        self.stack.push(scope)
+    }
+
+    pub fn pop_scope(&mut self) -> Option<Environment> {
         self.stack.pop()
     }
 
-    pub fn current_scope(&self) -> Option<&HashMap<String, Value>> {
-        self.stack.last()
+    /// Whether `key` is bound in the current scope itself.
+    pub fn is_local(&self, key: &str) -> bool {
+        self.current().borrow().variables.contains_key(key)
+    }
+
+    /// Whether the current scope is the global one.
+    pub fn is_global(&self) -> bool {
+        Rc::ptr_eq(self.current(), &self.global)
+    }
+
+    /// Have reads and assignments of `key` in the current scope use the global binding.
+    pub fn declare_global(&mut self, key: String) {
+        if !self.is_global() {
+            self.current().borrow_mut().globals.insert(key);
+        }
+    }
+
+    /// Have reads and assignments of `key` in the current scope use the binding of an enclosing scope.
+    /// Returns `false` if no enclosing function scope defines it.
+    pub fn declare_nonlocal(&mut self, key: String) -> bool {
+        if self.enclosing(&key).is_none() {
+            return false
+        }
+        self.current().borrow_mut().nonlocals.insert(key);
+        true
     }
 
-    pub fn current_scope_mut(&mut self) -> Option<&mut HashMap<String, Value>> {
-        self.stack.last_mut()
+    /// Names defined in any scope, the innermost scope first.
+    pub fn names(&self) -> Vec<String> {
+        let mut names: Vec<String> = Vec::new();
+        let mut scope = Some(self.current().clone());
+        while let Some(current) = scope {
+            for name in current.borrow().variables.keys() {
+                if !names.contains(name) {
+                    names.push(name.clone());
+                }
+            }
+            scope = current.borrow().parent.clone();
+        }
+        names
+    }
+
+    pub fn builtins_mut(&mut self) -> RefMut<'_, HashMap<String, Value>> {
+        RefMut::map(self.builtins.borrow_mut(), |scope| &mut scope.variables)
+    }
+
+    pub fn current_scope_mut(&mut self) -> RefMut<'_, HashMap<String, Value>> {
+        RefMut::map(self.current().borrow_mut(), |scope| &mut scope.variables)
+    }
+}
+
+impl Drop for Memory {
+    fn drop(&mut self) {
+        for scope in self.scopes.drain(..).filter_map(|scope| scope.upgrade()) {
+            // Taken out before being dropped, as dropping them may release other scopes
+            let variables = ::std::mem::take(&mut scope.borrow_mut().variables);
+            drop(variables);
+        }
     }
 }
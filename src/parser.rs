@@ -1,36 +1,269 @@
 use std::iter::Peekable;
-use std::iter::Flatten;
+use std::vec::IntoIter;
 
-use lexer::Lexer;
-use token::Token;
+use lexer::{Lexer, unexpected_indent};
+use token::{Token, KEYWORDS};
+use span::Span;
 use ast:: AST;
+use error::BjornError;
+use suggestion::suggest;
+
+
+/// Levels expressions and blocs can be nested, so that parsing them cannot overflow the stack.
+const MAX_NESTING: usize = 1000;
 
 pub struct Parser<'a> {
-    lexer: Peekable<Flatten<Lexer<'a>>>,
+    // Source of the tokens, until they are all read
+    lexer: Option<Lexer<'a>>,
+    tokens: Peekable<IntoIter<(Token, Span)>>,
+    // Span of the last processed token
+    previous: Span,
+    // Span of the end of the input
+    end: Span,
+    // Errors met so far, the parser recovers from them
+    errors: Vec<BjornError>,
+    // Number of loops around the current statement, within the current function
+    loops: usize,
+    // Number of function declarations around the current statement
+    functions: usize,
+    // Number of expressions and blocs around the current one
+    nesting: usize,
+    // Spans of the starred expressions of the current statement not known to be assignment targets
+    starred: Vec<Span>,
 }
 
 impl<'a> Parser<'a> {
 
     pub fn new(lexer: Lexer<'a>) -> Parser<'a>  {
-        Parser {lexer: lexer.flatten().peekable()}
+        Parser {
+            lexer: Some(lexer),
+            tokens: Vec::new().into_iter().peekable(),
+            previous: Span::default(),
+            end: Span::default(),
+            errors: Vec::new(),
+            loops: 0,
+            functions: 0,
+            nesting: 0,
+            starred: Vec::new(),
+        }
+    }
+
+    /// Parser of already scanned tokens, `end` being the end of the input.
+    pub fn from_tokens(tokens: Vec<(Token, Span)>, end: Span) -> Parser<'a> {
+        let tokens: Vec<(Token, Span)> = tokens.into_iter()
+            .filter(|(token, _)| !token.is_trivia())
+            .collect();
+        Parser {
+            lexer: None,
+            tokens: tokens.into_iter().peekable(),
+            previous: Span::default(),
+            end: end,
+            errors: Vec::new(),
+            loops: 0,
+            functions: 0,
+            nesting: 0,
+            starred: Vec::new(),
+        }
+    }
+
+    fn tokenize(&mut self) {
+        let mut lexer = match self.lexer.take() {
+            Some(lexer) => lexer,
+            None => return,
+        };
+        let mut tokens = Vec::new();
+        for lexeme in &mut lexer {
+            match lexeme {
+                Ok(lexeme) => tokens.extend(lexeme.into_iter().filter(|(token, _)| !token.is_trivia())),
+                // The lexer resumes after the invalid lexeme
+                Err(error) => self.errors.push(error),
+            }
+        }
+        self.tokens = tokens.into_iter().peekable();
+        self.end = lexer.location();
     }
 
     fn peek(&mut self) -> &Token {
-        self.lexer.peek().unwrap_or(&Token::EOF)
+        match self.tokens.peek() {
+            Some((token, _)) => token,
+            None => &Token::EOF,
+        }
+    }
+
+    fn peek_span(&mut self) -> Span {
+        match self.tokens.peek() {
+            Some((_, span)) => *span,
+            None => self.end,
+        }
+    }
+
+    /// Span from `start` to the end of the last processed token.
+    fn span_from(&self, start: Span) -> Span {
+        start.to(self.previous)
+    }
+
+    fn advance(&mut self) -> Token {
+        let (next, span) = self.tokens.next().unwrap_or((Token::EOF, self.end));
+        self.previous = span;
+        next
+    }
+
+    /// Process the next token, which must be `verification` if any.
+    /// An unexpected token is left in place for the error recovery.
+    fn process(&mut self, verification: Option<Token>) -> Result<Token, BjornError> {
+        if let Some(verification) = verification {
+            if *self.peek() != verification {
+                return Err(BjornError::SyntaxError(
+                    format!("expected {}, found {}", verification, self.peek()).into()
+                ).at(self.peek_span()))
+            }
+        }
+        Ok(self.advance())
+    }
+
+    /// Process the next token, which must be `verification`,
+    /// `help` tells the user how to fix the code otherwise.
+    fn expect(&mut self, verification: Token, help: &str) -> Result<Token, BjornError> {
+        self.process(Some(verification)).map_err(|error| error.with_help(help))
     }
 
-    fn process(&mut self, verification: Option<Token>) -> Token {
-        let next = self.lexer.next().unwrap_or(Token::EOF);
-        match verification {
-            Some(verification) => {
-                if next == verification {
-                    next
-                } else {
-                    panic!("Parser error.")
+    /// Skip tokens up to the beginning of the next statement.
+    fn synchronize(&mut self) {
+        loop {
+            match *self.peek() {
+                Token::EOF | Token::DEDENT => return,
+                Token::NEWLINE => {
+                    self.advance();
+                    // The bloc of a statement in error is still checked
+                    if *self.peek() == Token::INDENT {
+                        let _ = self.indented_bloc();
+                    }
+                    return
+                },
+                Token::INDENT => {
+                    self.skip_bloc();
+                    return
+                },
+                // `if` is left out as it does not always start a statement
+                Token::WHILE | Token::FOR | Token::DEF | Token::RETURN
+                | Token::BREAK | Token::CONTINUE | Token::GLOBAL | Token::NONLOCAL => return,
+                _ => {
+                    self.advance();
+                },
+            }
+        }
+    }
+
+    /// Skip an indented bloc, nested blocs included.
+    fn skip_bloc(&mut self) {
+        let mut depth = 0;
+        loop {
+            match self.advance() {
+                Token::INDENT => depth += 1,
+                Token::DEDENT => {
+                    depth -= 1;
+                    if depth == 0 {
+                        return
+                    }
+                },
+                Token::EOF => return,
+                _ => {},
+            }
+        }
+    }
+
+    /// Parse with `parse` one level deeper, failing past `MAX_NESTING` levels.
+    fn nested<T, F: FnOnce(&mut Self) -> Result<T, BjornError>>(&mut self, parse: F) -> Result<T, BjornError> {
+        if self.nesting >= MAX_NESTING {
+            return Err(BjornError::SyntaxError("too deeply nested".into())
+                .at(self.peek_span())
+                .with_help(&format!("expressions and blocs can be nested up to {} levels", MAX_NESTING)))
+        }
+        self.nesting += 1;
+        let result = parse(self);
+        self.nesting -= 1;
+        result
+    }
+
+    /// Fail on a starred expression parsed since the last assignment target.
+    fn reject_starred(&mut self) -> Result<(), BjornError> {
+        match self.starred.drain(..).next() {
+            Some(span) => Err(BjornError::SyntaxError(
+                "starred expressions can only be assignment targets".into()
+            ).at(span)),
+            None => Ok(()),
+        }
+    }
+
+    /// Check that `tree` can be assigned to, returning the spans of its starred targets.
+    fn assignment_target(&self, tree: &AST) -> Result<Vec<Span>, BjornError> {
+        match tree {
+            AST::Variable {..} => Ok(Vec::new()),
+            AST::Index {index, span, ..} => match **index {
+                AST::Slice {..} => Err(BjornError::SyntaxError("cannot assign to a slice".into()).at(*span)),
+                _ => Ok(Vec::new()),
+            },
+            AST::Tuple {elements, ..} => {
+                let mut starred = Vec::new();
+                let mut unpacked = false;
+                for element in elements {
+                    match element {
+                        AST::Starred {value, span} => {
+                            if unpacked {
+                                return Err(BjornError::SyntaxError(
+                                    "multiple starred expressions in assignment".into()
+                                ).at(*span))
+                            }
+                            unpacked = true;
+                            starred.push(*span);
+                            starred.extend(self.assignment_target(value)?);
+                        },
+                        element => starred.extend(self.assignment_target(element)?),
+                    }
                 }
+                Ok(starred)
             },
-            None => {
-                next
+            AST::Starred {span, ..} => Err(BjornError::SyntaxError(
+                "a starred assignment target must be in a tuple".into()
+            ).at(*span).with_help("add a comma after it, as in `*rest, = xs`")),
+            tree => Err(BjornError::SyntaxError(
+                "cannot assign to an expression".into()
+            ).at(tree.span())),
+        }
+    }
+
+    /// Parse a statement, or an `AST::Error` node if it is invalid.
+    fn recovering_statement(&mut self) -> AST {
+        let start = self.peek_span();
+        let first = self.peek().clone();
+        match self.statement() {
+            Ok(node) => node,
+            Err(error) => {
+                self.starred.clear();
+                // A statement failing right after its leading identifier
+                // is likely a misspelled keyword.
+                let error = match first {
+                    Token::ID(ref id) if self.previous == start => match suggest(id, KEYWORDS.iter().cloned()) {
+                        Some(keyword) => error.with_help(&format!("did you mean `{}`?", keyword)),
+                        None => error,
+                    },
+                    _ => error,
+                };
+                // A syntax error on the line of an invalid lexeme or indentation,
+                // or later in the same statement, is most likely caused by it,
+                // which is already reported.
+                let caused_by_lexeme = self.errors.iter().any(|e| match (e, e.span(), error.span()) {
+                    (BjornError::LexicalError(..), Some(cause), Some(span))
+                    | (BjornError::IndentationError(..), Some(cause), Some(span)) => {
+                        cause.line == span.line || (start.start <= cause.start && cause.start <= span.start)
+                    },
+                    _ => false,
+                });
+                if !caused_by_lexeme {
+                    self.errors.push(error);
+                }
+                self.synchronize();
+                AST::Error {span: self.span_from(start)}
             }
         }
     }
@@ -40,19 +273,27 @@ impl<'a> Parser<'a> {
         let mut children = Vec::new();
         while *self.peek() != Token::EOF {
             if *self.peek() == Token::NEWLINE {
-                self.process(Some(Token::NEWLINE));
+                self.advance();
             } else {
-                children.push(Box::new(self.statement()));
+                children.push(Box::new(self.recovering_statement()));
             }
         }
-        AST::Program {children: children}
+        let span = match (children.first(), children.last()) {
+            (Some(first), Some(last)) => first.span().to(last.span()),
+            _ => Span::default(),
+        };
+        AST::Program {children: children, span: span}
     }
 
     /// statement: compound_statement
     ///          | simple_statement
-    fn statement(&mut self) -> AST {
+    fn statement(&mut self) -> Result<AST, BjornError> {
+        if *self.peek() == Token::INDENT {
+            return Err(unexpected_indent(self.peek_span()))
+        }
         if *self.peek() == Token::IF
         || *self.peek() == Token::WHILE
+        || *self.peek() == Token::FOR
         || *self.peek() == Token::DEF {
             self.compound_statement()
         } else {
@@ -62,308 +303,721 @@ impl<'a> Parser<'a> {
 
     /// compound_statement: if_statement
     ///                   | while_statement
+    ///                   | for_statement
     ///                   | function_declaration
-    fn compound_statement(&mut self) -> AST {
+    fn compound_statement(&mut self) -> Result<AST, BjornError> {
         if *self.peek() == Token::IF {
             self.if_statement()
         } else if *self.peek() == Token::WHILE {
             self.while_statement()
+        } else if *self.peek() == Token::FOR {
+            self.for_statement()
         } else if *self.peek() == Token::DEF {
             self.function_declaration()
         } else {
-            panic!("Parser error.")
+            Err(BjornError::SyntaxError(
+                format!("expected a compound statement, found {}", self.peek()).into()
+            ).at(self.peek_span()))
         }
     }
 
     /// if_statement: 'if' logical_or_expr ':' bloc ('else' 'if' logical_or_expr ':' bloc)* ['else' ':' bloc]
-    fn if_statement(&mut self) -> AST {
-        self.process(Some(Token::IF));
-        let if_condition = self.logical_or_expr();
-        self.process(Some(Token::COLON));
-        let if_bloc = self.bloc();
+    fn if_statement(&mut self) -> Result<AST, BjornError> {
+        let start = self.peek_span();
+        self.process(Some(Token::IF))?;
+        let if_condition = self.logical_or_expr()?;
+        self.reject_starred()?;
+        self.expect(Token::COLON, "add `:` after the `if` condition")?;
+        let if_bloc = self.bloc()?;
+        let mut end = if_bloc.span();
 
         let mut else_if_compounds: Vec<(Box<AST>, Box<AST>)> = Vec::new();
         let mut else_compound: Box<AST> = Box::new(AST::Empty);
         while *self.peek() == Token::ELSE {
-            self.process(Some(Token::ELSE));
+            self.process(Some(Token::ELSE))?;
             if *self.peek() == Token::IF {
-                self.process(Some(Token::IF));
-                let else_if_condition = self.logical_or_expr();
-                self.process(Some(Token::COLON));
-                let else_if_bloc = self.bloc();
+                self.process(Some(Token::IF))?;
+                let else_if_condition = self.logical_or_expr()?;
+                self.reject_starred()?;
+                self.expect(Token::COLON, "add `:` after the `else if` condition")?;
+                let else_if_bloc = self.bloc()?;
+                end = else_if_bloc.span();
                 else_if_compounds.push(
                     (Box::new(else_if_condition), Box::new(else_if_bloc))
                 );
             } else {
-                self.process(Some(Token::COLON));
-                else_compound = Box::new(self.bloc());
+                self.expect(Token::COLON, "add `:` after `else`")?;
+                else_compound = Box::new(self.bloc()?);
+                end = else_compound.span();
             }
         }
-        AST::IfStatement {
+        Ok(AST::IfStatement {
             if_compound: (Box::new(if_condition), Box::new(if_bloc)),
             else_if_compounds: else_if_compounds,
             else_compound: else_compound,
-        }
+            span: start.to(end),
+        })
     }
 
     /// while_statement: 'while' logical_or_expr ':' bloc
-    fn while_statement(&mut self) -> AST {
-        self.process(Some(Token::WHILE));
-        let while_condition = self.logical_or_expr();
-        self.process(Some(Token::COLON));
-        let while_bloc = self.bloc();
-        AST::WhileStatement {
+    fn while_statement(&mut self) -> Result<AST, BjornError> {
+        let start = self.peek_span();
+        self.process(Some(Token::WHILE))?;
+        let while_condition = self.logical_or_expr()?;
+        self.reject_starred()?;
+        self.expect(Token::COLON, "add `:` after the `while` condition")?;
+        let while_bloc = self.loop_bloc()?;
+        Ok(AST::WhileStatement {
             condition: Box::new(while_condition),
+            span: start.to(while_bloc.span()),
             bloc: Box::new(while_bloc),
+        })
+    }
+
+    /// for_statement: 'for' ID (',' ID)* 'in' logical_or_expr ':' bloc
+    fn for_statement(&mut self) -> Result<AST, BjornError> {
+        let start = self.peek_span();
+        self.process(Some(Token::FOR))?;
+        let variables_start = self.peek_span();
+        let mut variables = Vec::new();
+        loop {
+            let span = self.peek_span();
+            match self.process(None)? {
+                token @ Token::ID(_) => variables.push(AST::Variable {id: token, span: span}),
+                token => return Err(BjornError::SyntaxError(
+                    format!("expected an identifier, found {}", token).into()
+                ).at(span).with_help("name the loop variable after `for`")),
+            }
+            if *self.peek() != Token::COMMA {
+                break;
+            }
+            self.process(Some(Token::COMMA))?;
         }
+        // Several variables unpack each value
+        let variable = if variables.len() == 1 {
+            variables.pop().unwrap()
+        } else {
+            AST::Tuple {elements: variables, span: self.span_from(variables_start)}
+        };
+        self.expect(Token::IN, "add `in` after the loop variable")?;
+        let iterable = self.logical_or_expr()?;
+        self.reject_starred()?;
+        self.expect(Token::COLON, "add `:` after the `for` iterable")?;
+        let for_bloc = self.loop_bloc()?;
+        Ok(AST::ForStatement {
+            variable: Box::new(variable),
+            iterable: Box::new(iterable),
+            span: start.to(for_bloc.span()),
+            bloc: Box::new(for_bloc),
+        })
     }
 
     /// function_declaration: 'def' ID parameters ':' suite
-    fn function_declaration(&mut self) -> AST {
-        self.process(Some(Token::DEF));
-        let identifier =  self.process(None);
+    fn function_declaration(&mut self) -> Result<AST, BjornError> {
+        let start = self.peek_span();
+        self.process(Some(Token::DEF))?;
+        let identifier =  self.process(None)?;
         match identifier {
             Token::ID(_) => {},
-            _ => panic!("Parser error.")
+            _ => return Err(BjornError::SyntaxError(
+                format!("expected a function name, found {}", identifier).into()
+            ).at(self.previous))
         };
-        let parameters = self.parameters();
-        self.process(Some(Token::COLON));
+        let parameters = self.parameters()?;
+        self.declared_parameters(&parameters)?;
+        self.expect(Token::COLON, "add `:` after the function parameters")?;
+        // Loops around the declaration cannot be controlled from its body
+        let loops = self.loops;
+        self.loops = 0;
+        self.functions += 1;
         let body = self.bloc();
+        self.functions -= 1;
+        self.loops = loops;
+        let body = body?;
 
-        AST::FunctionDeclaration {
+        Ok(AST::FunctionDeclaration {
             identifier: identifier,
             parameters: parameters,
+            span: start.to(body.span()),
             body: Box::new(body),
-        }
+        })
     }
 
     /// parameters: '(' [logical_or_expr (',' logical_or_expr)*] ')'
-    fn parameters(&mut self) -> Vec<AST> {
-        self.process(Some(Token::LPAREN));
+    fn parameters(&mut self) -> Result<Vec<AST>, BjornError> {
+        self.process(Some(Token::LPAREN))?;
         let mut parameters = Vec::new();
         while *self.peek() != Token::RPAREN {
-            parameters.push(AST::Parameter {parameter: Box::new(self.logical_or_expr())});
+            if *self.peek() == Token::EOF {
+                return Err(BjornError::SyntaxError(
+                    "expected `)`, found end of file".into()
+                ).at(self.end).with_help("close the parenthesis with `)`"))
+            }
+            let parameter = self.logical_or_expr()?;
+            parameters.push(AST::Parameter {span: parameter.span(), parameter: Box::new(parameter)});
             if *self.peek() == Token::COMMA {
-                self.process(Some(Token::COMMA));
+                self.process(Some(Token::COMMA))?;
             }
         }
-        self.process(Some(Token::RPAREN));
+        self.process(Some(Token::RPAREN))?;
         if parameters.is_empty() {
             parameters.push(AST::Empty)
         }
-        parameters
+        Ok(parameters)
+    }
+
+    /// Check that the `parameters` of a declaration are distinct identifiers.
+    fn declared_parameters(&self, parameters: &[AST]) -> Result<(), BjornError> {
+        let mut names = Vec::new();
+        for parameter in parameters {
+            if let AST::Parameter {parameter, span} = parameter {
+                match **parameter {
+                    AST::Variable {id: Token::ID(ref name), ..} if names.contains(&name) => {
+                        return Err(BjornError::SyntaxError(
+                            format!("duplicate parameter `{}`", name).into()
+                        ).at(*span))
+                    },
+                    AST::Variable {id: Token::ID(ref name), ..} => names.push(name),
+                    _ => return Err(BjornError::SyntaxError(
+                        "function parameters must be identifiers".into()
+                    ).at(*span)),
+                }
+            }
+        }
+        Ok(())
     }
 
     /// bloc: NEWLINE INDENT statement+ DEDENT
-    fn bloc(&mut self) -> AST {
-        self.process(Some(Token::NEWLINE));
-        self.process(Some(Token::INDENT));
+    fn bloc(&mut self) -> Result<AST, BjornError> {
+        self.expect(Token::NEWLINE, "a bloc starts on a new line after `:`")?;
+        self.nested(Self::indented_bloc)
+    }
+
+    /// Bloc of a loop, in which `break` and `continue` are allowed.
+    fn loop_bloc(&mut self) -> Result<AST, BjornError> {
+        self.loops += 1;
+        let bloc = self.bloc();
+        self.loops -= 1;
+        bloc
+    }
+
+    fn indented_bloc(&mut self) -> Result<AST, BjornError> {
+        self.expect(Token::INDENT, "indent the body of the bloc")?;
+        let start = self.peek_span();
         let mut children = Vec::new();
         while *self.peek() != Token::DEDENT {
-            children.push(Box::new(self.statement()));
+            if *self.peek() == Token::EOF {
+                self.errors.push(BjornError::SyntaxError(
+                    "unexpected end of file inside a bloc".into()
+                ).at(self.end));
+                break;
+            }
+            children.push(Box::new(self.recovering_statement()));
         }
-        self.process(Some(Token::DEDENT));
-        AST::Bloc {children:children}
+        let span = match children.last() {
+            Some(last) => start.to(last.span()),
+            None => start,
+        };
+        if *self.peek() == Token::DEDENT {
+            self.advance();
+        }
+        Ok(AST::Bloc {children:children, span: span})
     }
 
-    /// simple_statement: expression_statement NEWLINE
-    fn simple_statement(&mut self) -> AST {
-        let node;
-        if *self.peek() == Token::RETURN {
-            node = self.return_statement();
+    /// simple_statement: (expression_statement
+    ///                   | return_statement
+    ///                   | break_statement
+    ///                   | continue_statement
+    ///                   | global_statement
+    ///                   | nonlocal_statement) NEWLINE
+    fn simple_statement(&mut self) -> Result<AST, BjornError> {
+        let node = if *self.peek() == Token::RETURN {
+            self.return_statement()?
+        } else if *self.peek() == Token::BREAK || *self.peek() == Token::CONTINUE {
+            self.loop_control_statement()?
+        } else if *self.peek() == Token::GLOBAL || *self.peek() == Token::NONLOCAL {
+            self.declaration_statement()?
         } else {
-            node = self.expression_statement();
-        }
+            self.expression_statement()?
+        };
+        self.reject_starred()?;
 
         if *self.peek() != Token::EOF {
-            self.process(Some(Token::NEWLINE));
+            self.expect(Token::NEWLINE, "statements must be separated by a new line")?;
+        }
+        Ok(node)
+    }
+
+    /// return_statement: 'return' expression_list
+    fn return_statement(&mut self) -> Result<AST, BjornError> {
+        let start = self.peek_span();
+        self.process(Some(Token::RETURN))?;
+        let expression = self.expression_list()?;
+        Ok(AST::ReturnStatement {expression: Box::new(expression), span: self.span_from(start)})
+    }
+
+    /// break_statement: 'break'
+    /// continue_statement: 'continue'
+    fn loop_control_statement(&mut self) -> Result<AST, BjornError> {
+        let span = self.peek_span();
+        let token = self.process(None)?;
+        if self.loops == 0 {
+            return Err(BjornError::SyntaxError(
+                format!("{} outside a loop", token).into()
+            ).at(span))
         }
-        node
+        Ok(match token {
+            Token::BREAK => AST::BreakStatement {span: span},
+            _ => AST::ContinueStatement {span: span},
+        })
     }
 
-    /// return_statement: 'return' logical_or_expr
-    fn return_statement(&mut self) -> AST {
-        self.process(Some(Token::RETURN));
-        AST::ReturnStatement {expression: Box::new(self.logical_or_expr())}
+    /// global_statement: 'global' ID (',' ID)*
+    /// nonlocal_statement: 'nonlocal' ID (',' ID)*
+    fn declaration_statement(&mut self) -> Result<AST, BjornError> {
+        let start = self.peek_span();
+        let keyword = self.process(None)?;
+        if keyword == Token::NONLOCAL && self.functions == 0 {
+            return Err(BjornError::SyntaxError(
+                "`nonlocal` outside a function".into()
+            ).at(start).with_help("use `global` at the top level"))
+        }
+        let mut names = Vec::new();
+        loop {
+            let span = self.peek_span();
+            match self.process(None)? {
+                token @ Token::ID(_) => names.push(token),
+                token => return Err(BjornError::SyntaxError(
+                    format!("expected an identifier, found {}", token).into()
+                ).at(span).with_help(&format!("list the names after {}", keyword))),
+            }
+            if *self.peek() != Token::COMMA {
+                break;
+            }
+            self.process(Some(Token::COMMA))?;
+        }
+        let span = self.span_from(start);
+        Ok(match keyword {
+            Token::GLOBAL => AST::GlobalStatement {names: names, span: span},
+            _ => AST::NonlocalStatement {names: names, span: span},
+        })
     }
 
-    /// expression_statement: logical_or_expr ['=' logical_or_expr]
-    fn expression_statement(&mut self) -> AST {
-        let mut node = self.logical_or_expr();
+    /// expression_statement: expression_list [('=' | augmented_assign) expression_list]
+    fn expression_statement(&mut self) -> Result<AST, BjornError> {
+        let start = self.peek_span();
+        let mut node = self.expression_list()?;
         if *self.peek() == Token::ASSIGN {
-            self.process(Some(Token::ASSIGN));
-            let right = self.logical_or_expr();
+            let targets = self.assignment_target(&node)?;
+            self.starred.retain(|span| !targets.contains(span));
+            self.process(Some(Token::ASSIGN))?;
+            let right = self.expression_list()?;
             node = AST::Assignment {
-                left: Box::new(node), right: Box::new(right)
+                left: Box::new(node), right: Box::new(right), span: self.span_from(start)
+            };
+        } else if let Some(op) = self.peek().augmented_operator() {
+            match node {
+                AST::Tuple {span, ..} | AST::Starred {span, ..} => return Err(BjornError::SyntaxError(
+                    "cannot use augmented assignment with several targets".into()
+                ).at(span)),
+                _ => self.assignment_target(&node)?,
+            };
+            self.advance();
+            let right = self.expression_list()?;
+            node = AST::AugmentedAssignment {
+                left: Box::new(node), op: op, right: Box::new(right), span: self.span_from(start)
             };
         }
-        node
+        Ok(node)
     }
 
-    /// logical_or_expr: logical_and_expr ('or' logical_and_expr)*
-    fn logical_or_expr(&mut self) -> AST {
-        let mut node = self.logical_and_expr();
+    /// expression_list: star_expr (',' star_expr)* [',']
+    ///
+    /// A single expression without a trailing comma is not a tuple.
+    fn expression_list(&mut self) -> Result<AST, BjornError> {
+        let start = self.peek_span();
+        let first = self.star_expr()?;
+        if *self.peek() != Token::COMMA {
+            return Ok(first)
+        }
+        let mut elements = vec![first];
+        while *self.peek() == Token::COMMA {
+            self.process(Some(Token::COMMA))?;
+            match *self.peek() {
+                Token::NEWLINE | Token::EOF | Token::RPAREN | Token::ASSIGN => break,
+                ref token if token.augmented_operator().is_some() => break,
+                _ => elements.push(self.star_expr()?),
+            }
+        }
+        Ok(AST::Tuple {elements: elements, span: self.span_from(start)})
+    }
+
+    /// star_expr: '*' expr
+    ///          | logical_or_expr
+    fn star_expr(&mut self) -> Result<AST, BjornError> {
+        if *self.peek() != Token::MUL {
+            return self.logical_or_expr()
+        }
+        let start = self.peek_span();
+        self.process(Some(Token::MUL))?;
+        let value = self.expr()?;
+        let span = self.span_from(start);
+        self.starred.push(span);
+        Ok(AST::Starred {value: Box::new(value), span: span})
+    }
+
+    /// logical_or_expr: or_expr ['if' or_expr 'else' logical_or_expr]
+    fn logical_or_expr(&mut self) -> Result<AST, BjornError> {
+        self.nested(Self::conditional_expr)
+    }
+
+    fn conditional_expr(&mut self) -> Result<AST, BjornError> {
+        let start = self.peek_span();
+        let node = self.or_expr()?;
+        if *self.peek() != Token::IF {
+            return Ok(node)
+        }
+        self.process(Some(Token::IF))?;
+        let condition = self.or_expr()?;
+        self.expect(Token::ELSE, "add `else` and the value if the condition is false")?;
+        let else_branch = self.logical_or_expr()?;
+        Ok(AST::Conditional {
+            condition: Box::new(condition),
+            then_branch: Box::new(node),
+            else_branch: Box::new(else_branch),
+            span: self.span_from(start),
+        })
+    }
+
+    /// or_expr: logical_and_expr ('or' logical_and_expr)*
+    fn or_expr(&mut self) -> Result<AST, BjornError> {
+        let start = self.peek_span();
+        let mut node = self.logical_and_expr()?;
         loop {
             if *self.peek() == Token::OR {
-                let op = self.process(Some(Token::OR));
-                let right = self.logical_and_expr();
+                let op = self.process(Some(Token::OR))?;
+                let right = self.logical_and_expr()?;
                 node = AST::BinaryOperation {
                     left: Box::new(node),
                     op: op,
-                    right: Box::new(right)
+                    right: Box::new(right),
+                    span: self.span_from(start),
                 }
             } else {
                 break;
             }
         }
-        node
+        Ok(node)
     }
 
     /// logical_and_expr: logical_not_expr ('and' logical_not_expr)*
-    fn logical_and_expr(&mut self) -> AST {
-        let mut node = self.logical_not_expr();
+    fn logical_and_expr(&mut self) -> Result<AST, BjornError> {
+        let start = self.peek_span();
+        let mut node = self.logical_not_expr()?;
         loop {
             if *self.peek() == Token::AND {
-                let op = self.process(Some(Token::AND));
-                let right = self.logical_not_expr();
+                let op = self.process(Some(Token::AND))?;
+                let right = self.logical_not_expr()?;
                 node = AST::BinaryOperation {
                     left: Box::new(node),
                     op: op,
-                    right: Box::new(right)
+                    right: Box::new(right),
+                    span: self.span_from(start),
                 }
             } else {
                 break;
             }
         }
-        node
+        Ok(node)
     }
 
     /// logical_not_expr: 'not' logical_not_expr
     ///                 | comparison
-    fn logical_not_expr(&mut self) -> AST {
+    fn logical_not_expr(&mut self) -> Result<AST, BjornError> {
         if *self.peek() == Token::NOT {
-            let op = self.process(Some(Token::NOT));
-            let right = self.logical_not_expr();
-            AST::UnaryOperation {op: op, right: Box::new(right)}
+            let start = self.peek_span();
+            let op = self.process(Some(Token::NOT))?;
+            let right = self.nested(Self::logical_not_expr)?;
+            Ok(AST::UnaryOperation {op: op, right: Box::new(right), span: self.span_from(start)})
         } else {
             self.comparison()
         }
     }
 
-    /// comparison: expr (('==' | '!=' | '<=' | '>=' | '<' | '>') expr)*
-    fn comparison(&mut self) -> AST {
-        let mut node = self.expr();
+    /// comparison: expr (('==' | '!=' | '<=' | '>=' | '<' | '>' | 'in') expr)*
+    fn comparison(&mut self) -> Result<AST, BjornError> {
+        let start = self.peek_span();
+        let node = self.expr()?;
+        let mut operands = vec![node];
+        let mut operators = Vec::new();
+        while let Token::EQ | Token::NE | Token::LE | Token::GE | Token::LT | Token::GT | Token::IN = *self.peek() {
+            operators.push(self.process(None)?);
+            operands.push(self.expr()?);
+        }
+        if operators.is_empty() {
+            return Ok(operands.pop().unwrap())
+        }
+        // `a < b < c` compares `a < b` and `b < c`
+        Ok(AST::Comparison {operands: operands, operators: operators, span: self.span_from(start)})
+    }
+
+    /// expr: term (('+' | '-') term)*
+    fn expr(&mut self) -> Result<AST, BjornError> {
+        let start = self.peek_span();
+        let mut node = self.term()?;
         loop {
             let op: Token;
-            if *self.peek() == Token::EQ {
-                op = self.process(Some(Token::EQ));
-            } else if *self.peek() == Token::NE {
-                op = self.process(Some(Token::NE));
-            } else if *self.peek() == Token::LE {
-                op = self.process(Some(Token::LE));
-            } else if *self.peek() == Token::GE {
-                op = self.process(Some(Token::GE));
-            } else if *self.peek() == Token::LT {
-                op = self.process(Some(Token::LT));
-            } else if *self.peek() == Token::GT {
-                op = self.process(Some(Token::GT));
+            if *self.peek() == Token::PLUS {
+                op = self.process(Some(Token::PLUS))?;
+            } else if *self.peek() == Token::MINUS {
+                op = self.process(Some(Token::MINUS))?;
             } else {
                 break;
             }
-            let right = self.term();
+            let right = self.term()?;
             node = AST::BinaryOperation {
                 left: Box::new(node),
                 op: op,
-                right: Box::new(right)
+                right: Box::new(right),
+                span: self.span_from(start),
             }
         }
-        node
+        Ok(node)
     }
 
-    /// expr: term (('+' | '-') term)*
-    fn expr(&mut self) -> AST {
-        let mut node = self.term();
-        loop {
-            let op: Token;
-            if *self.peek() == Token::PLUS {
-                op = self.process(Some(Token::PLUS));
-            } else if *self.peek() == Token::MINUS {
-                op = self.process(Some(Token::MINUS));
-            } else {
-                break;
-            }
-            let right = self.term();
+    /// term: factor (('*' | '/' | '//' | '%') factor)*
+    fn term(&mut self) -> Result<AST, BjornError> {
+        let start = self.peek_span();
+        let mut node = self.factor()?;
+        while let Token::MUL | Token::DIV | Token::FLOORDIV | Token::MOD = *self.peek() {
+            let op = self.process(None)?;
+            let right = self.factor()?;
             node = AST::BinaryOperation {
                 left: Box::new(node),
                 op: op,
-                right: Box::new(right)
+                right: Box::new(right),
+                span: self.span_from(start),
             }
         }
-        node
+        Ok(node)
     }
 
-    /// term: atom (('*' | '/') atom)*
-    fn term(&mut self) -> AST {
-        let mut node = self.atom();
+    /// factor: ('+' | '-') factor
+    ///       | power
+    fn factor(&mut self) -> Result<AST, BjornError> {
+        let start = self.peek_span();
+        if *self.peek() == Token::PLUS || *self.peek() == Token::MINUS {
+            let op = self.process(None)?;
+            let right = self.nested(Self::factor)?;
+            return Ok(AST::UnaryOperation {op: op, right: Box::new(right), span: self.span_from(start)})
+        }
+        self.power()
+    }
+
+    /// power: call ['**' factor]
+    fn power(&mut self) -> Result<AST, BjornError> {
+        let start = self.peek_span();
+        let node = self.call()?;
+        if *self.peek() != Token::POW {
+            return Ok(node)
+        }
+        let op = self.process(Some(Token::POW))?;
+        // Right-associative, and binds tighter than a unary operator on its left
+        let right = self.nested(Self::factor)?;
+        Ok(AST::BinaryOperation {
+            left: Box::new(node),
+            op: op,
+            right: Box::new(right),
+            span: self.span_from(start),
+        })
+    }
+
+    /// call: atom (parameters | '[' subscript ']' | '.' ID)*
+    fn call(&mut self) -> Result<AST, BjornError> {
+        let start = self.peek_span();
+        let mut node = self.atom()?;
         loop {
-            let op: Token;
-            if *self.peek() == Token::MUL {
-                op = self.process(Some(Token::MUL));
-            } else if *self.peek() == Token::DIV {
-                op = self.process(Some(Token::DIV));
+            if *self.peek() == Token::LPAREN {
+                let arguments = self.parameters()?;
+                node = AST::FunctionCall {callee: Box::new(node), arguments: arguments, span: self.span_from(start)};
+            } else if *self.peek() == Token::LBRACKET {
+                self.process(Some(Token::LBRACKET))?;
+                let index = self.subscript()?;
+                self.expect(Token::RBRACKET, "close the brackets with `]`")?;
+                node = AST::Index {value: Box::new(node), index: Box::new(index), span: self.span_from(start)};
+            } else if *self.peek() == Token::DOT {
+                self.process(Some(Token::DOT))?;
+                let span = self.peek_span();
+                let name = match self.process(None)? {
+                    token @ Token::ID(_) => token,
+                    token => return Err(BjornError::SyntaxError(
+                        format!("expected an attribute name, found {}", token).into()
+                    ).at(span)),
+                };
+                node = AST::Attribute {value: Box::new(node), name: name, span: self.span_from(start)};
             } else {
                 break;
             }
-            let right = self.atom();
-            node = AST::BinaryOperation {
-                left: Box::new(node),
-                op: op,
-                right: Box::new(right)
+        }
+        Ok(node)
+    }
+
+    /// subscript: logical_or_expr
+    ///          | [logical_or_expr] ':' [logical_or_expr] [':' [logical_or_expr]]
+    fn subscript(&mut self) -> Result<AST, BjornError> {
+        let start = self.peek_span();
+        let first = if *self.peek() == Token::COLON {
+            AST::Empty
+        } else {
+            self.logical_or_expr()?
+        };
+        if *self.peek() != Token::COLON {
+            return Ok(first)
+        }
+        self.process(Some(Token::COLON))?;
+        let stop = self.slice_bound()?;
+        let step = if *self.peek() == Token::COLON {
+            self.process(Some(Token::COLON))?;
+            self.slice_bound()?
+        } else {
+            AST::Empty
+        };
+        Ok(AST::Slice {
+            start: Box::new(first),
+            stop: Box::new(stop),
+            step: Box::new(step),
+            span: self.span_from(start),
+        })
+    }
+
+    fn slice_bound(&mut self) -> Result<AST, BjornError> {
+        match *self.peek() {
+            Token::COLON | Token::RBRACKET => Ok(AST::Empty),
+            _ => self.logical_or_expr(),
+        }
+    }
+
+    /// list: '[' [logical_or_expr (',' logical_or_expr)* [',']] ']'
+    fn list(&mut self, start: Span) -> Result<AST, BjornError> {
+        let mut elements = Vec::new();
+        while *self.peek() != Token::RBRACKET {
+            if *self.peek() == Token::EOF {
+                return Err(BjornError::SyntaxError(
+                    "expected `]`, found end of file".into()
+                ).at(self.end).with_help("close the brackets with `]`"))
+            }
+            elements.push(self.logical_or_expr()?);
+            if *self.peek() != Token::COMMA {
+                break;
+            }
+            self.process(Some(Token::COMMA))?;
+        }
+        self.expect(Token::RBRACKET, "close the brackets with `]`")?;
+        Ok(AST::List {elements: elements, span: self.span_from(start)})
+    }
+
+    /// dict: '{' [logical_or_expr ':' logical_or_expr (',' logical_or_expr ':' logical_or_expr)* [',']] '}'
+    fn dict(&mut self, start: Span) -> Result<AST, BjornError> {
+        let mut entries = Vec::new();
+        while *self.peek() != Token::RBRACE {
+            if *self.peek() == Token::EOF {
+                return Err(BjornError::SyntaxError(
+                    "expected `}`, found end of file".into()
+                ).at(self.end).with_help("close the braces with `}`"))
+            }
+            let key = self.logical_or_expr()?;
+            self.expect(Token::COLON, "add `:` between the key and its value")?;
+            let value = self.logical_or_expr()?;
+            entries.push((key, value));
+            if *self.peek() != Token::COMMA {
+                break;
             }
+            self.process(Some(Token::COMMA))?;
         }
-        node
+        self.expect(Token::RBRACE, "close the braces with `}`")?;
+        Ok(AST::Dict {entries: entries, span: self.span_from(start)})
+    }
+
+    /// lambda: 'lambda' [ID (',' ID)*] ':' logical_or_expr
+    fn lambda(&mut self, start: Span) -> Result<AST, BjornError> {
+        let mut parameters = Vec::new();
+        while *self.peek() != Token::COLON {
+            let span = self.peek_span();
+            match self.process(None)? {
+                token @ Token::ID(_) => parameters.push(AST::Parameter {
+                    parameter: Box::new(AST::Variable {id: token, span: span}),
+                    span: span,
+                }),
+                token => return Err(BjornError::SyntaxError(
+                    format!("expected a parameter name, found {}", token).into()
+                ).at(span).with_help("add `:` after the parameters of the lambda")),
+            }
+            if *self.peek() != Token::COMMA {
+                break;
+            }
+            self.process(Some(Token::COMMA))?;
+        }
+        self.expect(Token::COLON, "add `:` after the parameters of the lambda")?;
+        self.declared_parameters(&parameters)?;
+        if parameters.is_empty() {
+            parameters.push(AST::Empty)
+        }
+        let body = self.logical_or_expr()?;
+        Ok(AST::Lambda {parameters: parameters, body: Box::new(body), span: self.span_from(start)})
     }
 
     /// atom: INT_NUMBER
     ///     | FLOAT_NUMBER
-    ///     | PLUS atom
-    ///     | MINUS atom
-    ///     | '(' expr ')'
+    ///     | STRING
+    ///     | '(' [expression_list] ')'
     ///     | TRUE
     ///     | FALSE
-    ///     | ID [parameters]
-    fn atom (&mut self) -> AST {
-        let token = self.process(None);
+    ///     | ID
+    ///     | list
+    ///     | dict
+    ///     | lambda
+    fn atom (&mut self) -> Result<AST, BjornError> {
+        let start = self.peek_span();
+        match *self.peek() {
+            Token::NEWLINE | Token::INDENT | Token::DEDENT => return Err(BjornError::SyntaxError(
+                format!("unexpected {}", self.peek()).into()
+            ).at(start).with_help("expected an expression")),
+            _ => {},
+        }
+        let token = self.process(None)?;
         match token {
-            Token::INT(_) => AST::IntNumber {token: token},
-            Token::FLOAT(_) => AST::FloatNumber {token: token},
-            Token::LPAREN => {
-                let expr = self.expr();
-                self.process(Some(Token::RPAREN));
-                expr
-            },
-            Token::PLUS => {
-                AST::UnaryOperation {op: token, right: Box::new(self.atom())}
+            Token::INT(_) => Ok(AST::IntNumber {token: token, span: start}),
+            Token::FLOAT(_) => Ok(AST::FloatNumber {token: token, span: start}),
+            Token::STRING(_) => Ok(AST::String {token: token, span: start}),
+            Token::LPAREN if *self.peek() == Token::RPAREN => {
+                self.process(Some(Token::RPAREN))?;
+                Ok(AST::Tuple {elements: Vec::new(), span: self.span_from(start)})
             },
-            Token::MINUS => {
-                AST::UnaryOperation {op: token, right: Box::new(self.atom())}
-            },
-            Token::BOOL(value) => AST::Boolean {token: Token::BOOL(value)},
-            Token::ID(_) => {
-                if *self.peek() == Token::LPAREN {
-                    let arguments = self.parameters();
-                    AST::FunctionCall {identifier: token, arguments: arguments}
-                } else {
-                    AST::Variable {id: token}
-                }
+            Token::LPAREN => {
+                let expr = self.expression_list()?;
+                self.expect(Token::RPAREN, "close the parenthesis with `)`")?;
+                Ok(expr)
             },
-            Token::EOF => AST::Empty,
-            _ => panic!("Syntax error."),
+            Token::BOOL(value) => Ok(AST::Boolean {token: Token::BOOL(value), span: start}),
+            Token::ID(_) => Ok(AST::Variable {id: token, span: start}),
+            Token::LBRACKET => self.list(start),
+            Token::LBRACE => self.dict(start),
+            Token::LAMBDA => self.lambda(start),
+            Token::EOF => Ok(AST::Empty),
+            _ => Err(BjornError::SyntaxError(
+                format!("unexpected {}", token).into()
+            ).at(start).with_help("expected an expression")),
         }
     }
 
-    pub fn parse(&mut self) -> AST {
-        self.program()
+    /// Parse the whole input, recovering from syntax errors.
+    /// Returns the best-effort tree along with every error met, in source order.
+    pub fn parse(&mut self) -> (AST, Vec<BjornError>) {
+        self.tokenize();
+        let tree = self.program();
+        let mut errors: Vec<BjornError> = self.errors.drain(..).collect();
+        errors.sort_by_key(|error| error.span().map(|span| span.start));
+        (tree, errors)
     }
 }
 
@@ -373,402 +1027,982 @@ mod tests {
 
     use lexer::Lexer;
     use token::Token;
+    use span::Span;
     use ast::AST;
 
-    fn parser_generator(input: &str) -> Parser {
+    fn parser_generator(input: &str) -> Parser<'_> {
         Parser::new(
             Lexer::new(input)
         )
     }
 
+    fn parse_valid(parser: &mut Parser) -> AST {
+        let (tree, errors) = parser.parse();
+        assert_eq!(errors, vec![]);
+        tree
+    }
+
+    /// Span on the first line of the input.
+    fn span(start: usize, end: usize) -> Span {
+        Span::new(start, end, 1, start + 1)
+    }
+
     #[test]
     fn function_call() {
         let mut parser = parser_generator("test(1)");
-        assert_eq!(parser.parse(),
+        assert_eq!(parse_valid(&mut parser),
             AST::Program { children: vec!(
                 Box::new(AST::FunctionCall {
-                    identifier: {Token::ID(String::from("test"))},
+                    callee: Box::new(AST::Variable {id: Token::ID(String::from("test")), span: span(0, 4)}),
                     arguments: vec![AST::Parameter {
-                        parameter: Box::new(AST::IntNumber {token: Token::INT(String::from("1"))}),
-                    }]
+                        parameter: Box::new(AST::IntNumber {token: Token::INT(String::from("1")), span: span(5, 6)}),
+                        span: span(5, 6),
+                    }],
+                    span: span(0, 7),
                 })
-            )}
+            ), span: span(0, 7)}
         );
     }
 
+    #[test]
+    fn chained_function_calls() {
+        let mut parser = parser_generator("f()(1)");
+        assert_eq!(parse_valid(&mut parser),
+            AST::Program { children: vec!(
+                Box::new(AST::FunctionCall {
+                    callee: Box::new(AST::FunctionCall {
+                        callee: Box::new(AST::Variable {id: Token::ID(String::from("f")), span: span(0, 1)}),
+                        arguments: vec![AST::Empty],
+                        span: span(0, 3),
+                    }),
+                    arguments: vec![AST::Parameter {
+                        parameter: Box::new(AST::IntNumber {token: Token::INT(String::from("1")), span: span(4, 5)}),
+                        span: span(4, 5),
+                    }],
+                    span: span(0, 6),
+                })
+            ), span: span(0, 6)}
+        );
+    }
+
+    #[test]
+    fn list() {
+        let mut parser = parser_generator("[1, [],]");
+        assert_eq!(parse_valid(&mut parser),
+            AST::Program { children: vec!(
+                Box::new(AST::List {
+                    elements: vec![
+                        AST::IntNumber {token: Token::INT(String::from("1")), span: span(1, 2)},
+                        AST::List {elements: vec![], span: span(4, 6)},
+                    ],
+                    span: span(0, 8),
+                })
+            ), span: span(0, 8)}
+        );
+        for input in &["[1, 2", "[1 2]", "[,]"] {
+            let mut parser = parser_generator(input);
+            let (_, errors) = parser.parse();
+            assert_eq!(errors.len(), 1, "expected an error for {:?}", input);
+        }
+    }
+
+    #[test]
+    fn index_and_slice() {
+        let mut parser = parser_generator("a[0][::2]");
+        assert_eq!(parse_valid(&mut parser),
+            AST::Program { children: vec!(
+                Box::new(AST::Index {
+                    value: Box::new(AST::Index {
+                        value: Box::new(AST::Variable {id: Token::ID(String::from("a")), span: span(0, 1)}),
+                        index: Box::new(AST::IntNumber {token: Token::INT(String::from("0")), span: span(2, 3)}),
+                        span: span(0, 4),
+                    }),
+                    index: Box::new(AST::Slice {
+                        start: Box::new(AST::Empty),
+                        stop: Box::new(AST::Empty),
+                        step: Box::new(AST::IntNumber {token: Token::INT(String::from("2")), span: span(7, 8)}),
+                        span: span(5, 8),
+                    }),
+                    span: span(0, 9),
+                })
+            ), span: span(0, 9)}
+        );
+        for input in &["a[]", "a[1", "a[1:2:3:4]"] {
+            let mut parser = parser_generator(input);
+            let (_, errors) = parser.parse();
+            assert_eq!(errors.len(), 1, "expected an error for {:?}", input);
+        }
+    }
+
+    #[test]
+    fn dict() {
+        let mut parser = parser_generator("{'a': 1,}.keys");
+        assert_eq!(parse_valid(&mut parser),
+            AST::Program { children: vec!(
+                Box::new(AST::Attribute {
+                    value: Box::new(AST::Dict {
+                        entries: vec![(
+                            AST::String {token: Token::STRING(String::from("a")), span: span(1, 4)},
+                            AST::IntNumber {token: Token::INT(String::from("1")), span: span(6, 7)},
+                        )],
+                        span: span(0, 9),
+                    }),
+                    name: Token::ID(String::from("keys")),
+                    span: span(0, 14),
+                })
+            ), span: span(0, 14)}
+        );
+        for input in &["{'a' 1}", "{'a': 1", "{1: 2 3: 4}", "a.1"] {
+            let mut parser = parser_generator(input);
+            let (_, errors) = parser.parse();
+            assert_eq!(errors.len(), 1, "expected an error for {:?}", input);
+        }
+    }
+
+    #[test]
+    fn lambda() {
+        let mut parser = parser_generator("lambda x, y: x");
+        assert_eq!(parse_valid(&mut parser),
+            AST::Program { children: vec!(
+                Box::new(AST::Lambda {
+                    parameters: vec![
+                        AST::Parameter {
+                            parameter: Box::new(AST::Variable {id: Token::ID(String::from("x")), span: span(7, 8)}),
+                            span: span(7, 8),
+                        },
+                        AST::Parameter {
+                            parameter: Box::new(AST::Variable {id: Token::ID(String::from("y")), span: span(10, 11)}),
+                            span: span(10, 11),
+                        },
+                    ],
+                    body: Box::new(AST::Variable {id: Token::ID(String::from("x")), span: span(13, 14)}),
+                    span: span(0, 14),
+                })
+            ), span: span(0, 14)}
+        );
+        for input in &["lambda 1: 1", "lambda x y: 1", "lambda x"] {
+            let mut parser = parser_generator(input);
+            let (_, errors) = parser.parse();
+            assert_eq!(errors.len(), 1, "expected an error for {:?}", input);
+        }
+    }
+
+    #[test]
+    fn tuple() {
+        let mut parser = parser_generator("(), (1,), (1)");
+        assert_eq!(parse_valid(&mut parser),
+            AST::Program { children: vec!(
+                Box::new(AST::Tuple {
+                    elements: vec![
+                        AST::Tuple {elements: vec![], span: span(0, 2)},
+                        AST::Tuple {
+                            elements: vec![AST::IntNumber {token: Token::INT(String::from("1")), span: span(5, 6)}],
+                            span: span(5, 7),
+                        },
+                        AST::IntNumber {token: Token::INT(String::from("1")), span: span(11, 12)},
+                    ],
+                    span: span(0, 13),
+                })
+            ), span: span(0, 13)}
+        );
+        for input in &["(1, 2", "1, , 2", "(,)", "a, b += 1"] {
+            let mut parser = parser_generator(input);
+            let (_, errors) = parser.parse();
+            assert_eq!(errors.len(), 1, "expected an error for {:?}", input);
+        }
+    }
+
+    #[test]
+    fn unpacking_assignment() {
+        let mut parser = parser_generator("a, *b = c,");
+        assert_eq!(parse_valid(&mut parser),
+            AST::Program { children: vec!(
+                Box::new(AST::Assignment {
+                    left: Box::new(AST::Tuple {
+                        elements: vec![
+                            AST::Variable {id: Token::ID(String::from("a")), span: span(0, 1)},
+                            AST::Starred {
+                                value: Box::new(AST::Variable {id: Token::ID(String::from("b")), span: span(4, 5)}),
+                                span: span(3, 5),
+                            },
+                        ],
+                        span: span(0, 5),
+                    }),
+                    right: Box::new(AST::Tuple {
+                        elements: vec![AST::Variable {id: Token::ID(String::from("c")), span: span(8, 9)}],
+                        span: span(8, 10),
+                    }),
+                    span: span(0, 10),
+                })
+            ), span: span(0, 10)}
+        );
+    }
+
+    #[test]
+    fn invalid_assignment_targets() {
+        for (input, message) in &[
+            ("1 = 2", "cannot assign to an expression"),
+            ("f() = 1", "cannot assign to an expression"),
+            ("a, b + 1 = 1, 2", "cannot assign to an expression"),
+            ("f() += 1", "cannot assign to an expression"),
+            ("xs[0:1] = [2]", "cannot assign to a slice"),
+            ("xs[:] += [2]", "cannot assign to a slice"),
+        ] {
+            let mut parser = parser_generator(input);
+            let (_, errors) = parser.parse();
+            assert_eq!(errors.len(), 1, "expected an error for {:?}, got {:?}", input, errors);
+            assert_eq!(errors[0].message(), *message);
+        }
+    }
+
+    #[test]
+    fn invalid_starred_expressions() {
+        for input in &["(a, *b), *c = 1, 2", "a, *(b, *c) = 1, 2"] {
+            let mut parser = parser_generator(input);
+            let (_, errors) = parser.parse();
+            assert!(errors.is_empty(), "unexpected errors for {:?}: {:?}", input, errors);
+        }
+        for (input, message) in &[
+            ("a, *b, *c = 1, 2, 3", "multiple starred expressions in assignment"),
+            ("*a = [1]", "a starred assignment target must be in a tuple"),
+            ("x = *y", "starred expressions can only be assignment targets"),
+            ("a, b = 1, *c", "starred expressions can only be assignment targets"),
+            ("*a, b", "starred expressions can only be assignment targets"),
+            ("f((*a, b))", "starred expressions can only be assignment targets"),
+            ("while (*a,):\n    b\n", "starred expressions can only be assignment targets"),
+            ("*a += 1", "cannot use augmented assignment with several targets"),
+        ] {
+            let mut parser = parser_generator(input);
+            let (_, errors) = parser.parse();
+            assert_eq!(errors.len(), 1, "expected an error for {:?}, got {:?}", input, errors);
+            assert_eq!(errors[0].message(), *message);
+        }
+    }
+
     #[test]
     fn function_declaration() {
         let mut parser = parser_generator("def test():\n    return true\n");
-        assert_eq!(parser.parse(),
+        assert_eq!(parse_valid(&mut parser),
             AST::Program { children: vec!(
                 Box::new(AST::FunctionDeclaration{
                     identifier: Token::ID(String::from("test")),
                     parameters: vec![AST::Empty],
                     body: Box::new(AST::Bloc { children: vec![
                         Box::new(AST::ReturnStatement {
-                            expression: Box::new(AST::Boolean {token: Token::BOOL(true)}),
+                            expression: Box::new(AST::Boolean {token: Token::BOOL(true), span: Span::new(23, 27, 2, 12)}),
+                            span: Span::new(16, 27, 2, 5),
                         })
-                    ]})
+                    ], span: Span::new(16, 27, 2, 5)}),
+                    span: span(0, 27),
                 })
-            )}
+            ), span: span(0, 27)}
         );
     }
 
+    #[test]
+    fn invalid_parameters() {
+        for (input, message) in &[
+            ("def f(a, a):\n    return a\n", "duplicate parameter `a`"),
+            ("def g():\n    def f(a, b, a):\n        return a\n    return f\n", "duplicate parameter `a`"),
+            ("def f(a + 1):\n    return a\n", "function parameters must be identifiers"),
+            ("f = lambda a, a: a", "duplicate parameter `a`"),
+        ] {
+            let mut parser = parser_generator(input);
+            let (_, errors) = parser.parse();
+            assert_eq!(errors.len(), 1, "expected an error for {:?}, got {:?}", input, errors);
+            assert_eq!(errors[0].message(), *message);
+        }
+    }
+
     #[test]
     fn return_statement() {
         let mut parser = parser_generator("return true");
-        assert_eq!(parser.parse(),
+        assert_eq!(parse_valid(&mut parser),
             AST::Program { children: vec!(
                 Box::new(AST::ReturnStatement {
-                    expression: Box::new(AST::Boolean {token: Token::BOOL(true)}),
+                    expression: Box::new(AST::Boolean {token: Token::BOOL(true), span: span(7, 11)}),
+                    span: span(0, 11),
                 })
-            )}
+            ), span: span(0, 11)}
         );
     }
 
     #[test]
     fn while_statement() {
         let mut parser = parser_generator("while true:\n    1\n");
-        assert_eq!(parser.parse(),
+        assert_eq!(parse_valid(&mut parser),
             AST::Program { children: vec![
                 Box::new(AST::WhileStatement {
-                    condition: Box::new(AST::Boolean { token: Token::BOOL(true) }),
+                    condition: Box::new(AST::Boolean { token: Token::BOOL(true), span: span(6, 10) }),
                     bloc: Box::new(AST::Bloc { children:
-                        vec![Box::new(AST::IntNumber { token: Token::INT(String::from("1")) })]
-                    })
-                })]
+                        vec![Box::new(AST::IntNumber { token: Token::INT(String::from("1")), span: Span::new(16, 17, 2, 5) })],
+                        span: Span::new(16, 17, 2, 5),
+                    }),
+                    span: span(0, 17),
+                })],
+                span: span(0, 17),
             }
         );
     }
 
+    #[test]
+    fn for_statement() {
+        let mut parser = parser_generator("for i in range(2):\n    i\n");
+        assert_eq!(parse_valid(&mut parser),
+            AST::Program { children: vec![
+                Box::new(AST::ForStatement {
+                    variable: Box::new(AST::Variable { id: Token::ID(String::from("i")), span: span(4, 5) }),
+                    iterable: Box::new(AST::FunctionCall {
+                        callee: Box::new(AST::Variable { id: Token::ID(String::from("range")), span: span(9, 14) }),
+                        arguments: vec![AST::Parameter {
+                            parameter: Box::new(AST::IntNumber { token: Token::INT(String::from("2")), span: span(15, 16) }),
+                            span: span(15, 16),
+                        }],
+                        span: span(9, 17),
+                    }),
+                    bloc: Box::new(AST::Bloc { children:
+                        vec![Box::new(AST::Variable { id: Token::ID(String::from("i")), span: Span::new(23, 24, 2, 5) })],
+                        span: Span::new(23, 24, 2, 5),
+                    }),
+                    span: span(0, 24),
+                })],
+                span: span(0, 24),
+            }
+        );
+    }
+
+    #[test]
+    fn invalid_for_statement() {
+        for input in &["for 1 in a:\n    b\n", "for a b:\n    b\n", "for a in b\n    b\n"] {
+            let mut parser = parser_generator(input);
+            let (_, errors) = parser.parse();
+            assert_eq!(errors.len(), 1, "expected an error for {:?}", input);
+        }
+    }
+
+    #[test]
+    fn loop_control_statements() {
+        let mut parser = parser_generator("while true:\n    break\n    continue\n");
+        assert_eq!(parse_valid(&mut parser),
+            AST::Program { children: vec![
+                Box::new(AST::WhileStatement {
+                    condition: Box::new(AST::Boolean { token: Token::BOOL(true), span: span(6, 10) }),
+                    bloc: Box::new(AST::Bloc { children: vec![
+                            Box::new(AST::BreakStatement { span: Span::new(16, 21, 2, 5) }),
+                            Box::new(AST::ContinueStatement { span: Span::new(26, 34, 3, 5) }),
+                        ],
+                        span: Span::new(16, 34, 2, 5),
+                    }),
+                    span: span(0, 34),
+                })],
+                span: span(0, 34),
+            }
+        );
+    }
+
+    #[test]
+    fn loop_control_outside_loop() {
+        for input in &["break\n", "if true:\n    continue\n", "while true:\n    def f():\n        break\n"] {
+            let mut parser = parser_generator(input);
+            let (_, errors) = parser.parse();
+            match errors.as_slice() {
+                [BjornError::SyntaxError(diagnostic)] => assert!(diagnostic.message.ends_with("outside a loop")),
+                other => panic!("expected one error for {:?}, got {:?}", input, other),
+            }
+        }
+    }
+
     #[test]
     fn if_statement() {
         let mut parser = parser_generator("if true:\n    a = 1\nelse:\n    a = 2\n");
-        assert_eq!(parser.parse(),
+        assert_eq!(parse_valid(&mut parser),
             AST::Program { children: vec!(
                 Box::new(AST::IfStatement {
                     if_compound: (
-                        Box::new(AST::Boolean {token: Token::BOOL(true)}),
+                        Box::new(AST::Boolean {token: Token::BOOL(true), span: span(3, 7)}),
                         Box::new(AST::Bloc {children:
                             vec![Box::new(AST::Assignment {
-                                left: Box::new(AST::Variable {id: Token::ID(String::from("a"))}),
-                                right: Box::new(AST::IntNumber {token: Token::INT(String::from("1"))}),
-                            })]
+                                left: Box::new(AST::Variable {id: Token::ID(String::from("a")), span: Span::new(13, 14, 2, 5)}),
+                                right: Box::new(AST::IntNumber {token: Token::INT(String::from("1")), span: Span::new(17, 18, 2, 9)}),
+                                span: Span::new(13, 18, 2, 5),
+                            })],
+                            span: Span::new(13, 18, 2, 5),
                         })
                     ),
                     else_if_compounds: Vec::new(),
                     else_compound: Box::new(AST::Bloc {children:
                         vec![Box::new(AST::Assignment {
-                            left: Box::new(AST::Variable {id: Token::ID(String::from("a"))}),
-                            right: Box::new(AST::IntNumber {token: Token::INT(String::from("2"))}),
-                        })]
-                    })
+                            left: Box::new(AST::Variable {id: Token::ID(String::from("a")), span: Span::new(29, 30, 4, 5)}),
+                            right: Box::new(AST::IntNumber {token: Token::INT(String::from("2")), span: Span::new(33, 34, 4, 9)}),
+                            span: Span::new(29, 34, 4, 5),
+                        })],
+                        span: Span::new(29, 34, 4, 5),
+                    }),
+                    span: span(0, 34),
+                })
+            ), span: span(0, 34)}
+        );
+    }
+
+    #[test]
+    fn declaration_statements() {
+        let mut parser = parser_generator("def f():\n    global a, b\n    nonlocal c\n");
+        assert_eq!(parse_valid(&mut parser),
+            AST::Program { children: vec!(
+                Box::new(AST::FunctionDeclaration {
+                    identifier: Token::ID(String::from("f")),
+                    parameters: vec![AST::Empty],
+                    body: Box::new(AST::Bloc { children: vec![
+                            Box::new(AST::GlobalStatement {
+                                names: vec![Token::ID(String::from("a")), Token::ID(String::from("b"))],
+                                span: Span::new(13, 24, 2, 5),
+                            }),
+                            Box::new(AST::NonlocalStatement {
+                                names: vec![Token::ID(String::from("c"))],
+                                span: Span::new(29, 39, 3, 5),
+                            }),
+                        ],
+                        span: Span::new(13, 39, 2, 5),
+                    }),
+                    span: span(0, 39),
                 })
-            )}
+            ), span: span(0, 39)}
         );
     }
 
+    #[test]
+    fn invalid_declaration_statements() {
+        for input in &["nonlocal a\n", "def f():\n    global\n", "def f():\n    global 1\n"] {
+            let mut parser = parser_generator(input);
+            let (_, errors) = parser.parse();
+            assert_eq!(errors.len(), 1, "expected an error for {:?}", input);
+        }
+    }
+
     #[test]
     fn assignment_statement() {
         let mut parser = parser_generator("a = 1");
-        assert_eq!(parser.parse(),
+        assert_eq!(parse_valid(&mut parser),
             AST::Program { children: vec!(
                 Box::new(AST::Assignment {
-                    left: Box::new(AST::Variable {id: Token::ID(String::from("a"))}),
-                    right: Box::new(AST::IntNumber {token: Token::INT(String::from("1"))}),
+                    left: Box::new(AST::Variable {id: Token::ID(String::from("a")), span: span(0, 1)}),
+                    right: Box::new(AST::IntNumber {token: Token::INT(String::from("1")), span: span(4, 5)}),
+                    span: span(0, 5),
+                })
+            ), span: span(0, 5)}
+        );
+    }
+
+    #[test]
+    fn augmented_assignment_statement() {
+        let mut parser = parser_generator("a **= 2");
+        assert_eq!(parse_valid(&mut parser),
+            AST::Program { children: vec!(
+                Box::new(AST::AugmentedAssignment {
+                    left: Box::new(AST::Variable {id: Token::ID(String::from("a")), span: span(0, 1)}),
+                    op: Token::POW,
+                    right: Box::new(AST::IntNumber {token: Token::INT(String::from("2")), span: span(6, 7)}),
+                    span: span(0, 7),
                 })
-            )}
+            ), span: span(0, 7)}
         );
     }
 
+    #[test]
+    fn conditional_expression() {
+        let mut parser = parser_generator("a if b else c");
+        assert_eq!(parse_valid(&mut parser),
+            AST::Program { children: vec!(
+                Box::new(AST::Conditional {
+                    condition: Box::new(AST::Variable {id: Token::ID(String::from("b")), span: span(5, 6)}),
+                    then_branch: Box::new(AST::Variable {id: Token::ID(String::from("a")), span: span(0, 1)}),
+                    else_branch: Box::new(AST::Variable {id: Token::ID(String::from("c")), span: span(12, 13)}),
+                    span: span(0, 13),
+                })
+            ), span: span(0, 13)}
+        );
+        let mut parser = parser_generator("a if b\n");
+        let (_, errors) = parser.parse();
+        assert_eq!(errors.len(), 1);
+    }
+
     #[test]
     fn logical_or_operation() {
         let mut parser = parser_generator("true or true");
-        assert_eq!(parser.parse(),
+        assert_eq!(parse_valid(&mut parser),
             AST::Program { children: vec!(
                 Box::new(AST::BinaryOperation {
-                    left: Box::new(AST::Boolean {token: Token::BOOL(true)}),
+                    left: Box::new(AST::Boolean {token: Token::BOOL(true), span: span(0, 4)}),
                     op: Token::OR,
-                    right: Box::new(AST::Boolean {token: Token::BOOL(true)}),
+                    right: Box::new(AST::Boolean {token: Token::BOOL(true), span: span(8, 12)}),
+                    span: span(0, 12),
                 })
-            )}
+            ), span: span(0, 12)}
         );
     }
 
     #[test]
     fn logical_and_operation() {
         let mut parser = parser_generator("true and true");
-        assert_eq!(parser.parse(),
+        assert_eq!(parse_valid(&mut parser),
             AST::Program { children: vec!(
                 Box::new(AST::BinaryOperation {
-                    left: Box::new(AST::Boolean {token: Token::BOOL(true)}),
+                    left: Box::new(AST::Boolean {token: Token::BOOL(true), span: span(0, 4)}),
                     op: Token::AND,
-                    right: Box::new(AST::Boolean {token: Token::BOOL(true)}),
+                    right: Box::new(AST::Boolean {token: Token::BOOL(true), span: span(9, 13)}),
+                    span: span(0, 13),
                 })
-            )}
+            ), span: span(0, 13)}
         );
     }
 
     #[test]
     fn logical_not_operation() {
         let mut parser = parser_generator("not true");
-        assert_eq!(parser.parse(),
+        assert_eq!(parse_valid(&mut parser),
             AST::Program { children: vec!(
                 Box::new(AST::UnaryOperation {
                     op: Token::NOT,
-                    right: Box::new(AST::Boolean {token: Token::BOOL(true)}),
+                    right: Box::new(AST::Boolean {token: Token::BOOL(true), span: span(4, 8)}),
+                    span: span(0, 8),
                 })
-            )}
+            ), span: span(0, 8)}
         );
     }
 
     #[test]
     fn comparison_eq() {
         let mut parser = parser_generator("1 == 1");
-        assert_eq!(parser.parse(),
+        assert_eq!(parse_valid(&mut parser),
             AST::Program { children: vec!(
-                Box::new(AST::BinaryOperation {
-                    left: Box::new(AST::IntNumber {token: Token::INT(String::from("1"))}),
-                    op: Token::EQ,
-                    right: Box::new(AST::IntNumber {token: Token::INT(String::from("1"))}),
+                Box::new(AST::Comparison {
+                    operands: vec![
+                        AST::IntNumber {token: Token::INT(String::from("1")), span: span(0, 1)},
+                        AST::IntNumber {token: Token::INT(String::from("1")), span: span(5, 6)},
+                    ],
+                    operators: vec![Token::EQ],
+                    span: span(0, 6),
                 })
-            )}
+            ), span: span(0, 6)}
         );
     }
 
     #[test]
     fn comparison_ne() {
         let mut parser = parser_generator("1 != 1");
-        assert_eq!(parser.parse(),
+        assert_eq!(parse_valid(&mut parser),
             AST::Program { children: vec!(
-                Box::new(AST::BinaryOperation {
-                    left: Box::new(AST::IntNumber {token: Token::INT(String::from("1"))}),
-                    op: Token::NE,
-                    right: Box::new(AST::IntNumber {token: Token::INT(String::from("1"))}),
+                Box::new(AST::Comparison {
+                    operands: vec![
+                        AST::IntNumber {token: Token::INT(String::from("1")), span: span(0, 1)},
+                        AST::IntNumber {token: Token::INT(String::from("1")), span: span(5, 6)},
+                    ],
+                    operators: vec![Token::NE],
+                    span: span(0, 6),
                 })
-            )}
+            ), span: span(0, 6)}
         );
     }
 
     #[test]
     fn comparison_le() {
         let mut parser = parser_generator("1 <= 1");
-        assert_eq!(parser.parse(),
+        assert_eq!(parse_valid(&mut parser),
             AST::Program { children: vec!(
-                Box::new(AST::BinaryOperation {
-                    left: Box::new(AST::IntNumber {token: Token::INT(String::from("1"))}),
-                    op: Token::LE,
-                    right: Box::new(AST::IntNumber {token: Token::INT(String::from("1"))}),
+                Box::new(AST::Comparison {
+                    operands: vec![
+                        AST::IntNumber {token: Token::INT(String::from("1")), span: span(0, 1)},
+                        AST::IntNumber {token: Token::INT(String::from("1")), span: span(5, 6)},
+                    ],
+                    operators: vec![Token::LE],
+                    span: span(0, 6),
                 })
-            )}
+            ), span: span(0, 6)}
         );
     }
 
     #[test]
     fn comparison_ge() {
         let mut parser = parser_generator("1 >= 1");
-        assert_eq!(parser.parse(),
+        assert_eq!(parse_valid(&mut parser),
             AST::Program { children: vec!(
-                Box::new(AST::BinaryOperation {
-                    left: Box::new(AST::IntNumber {token: Token::INT(String::from("1"))}),
-                    op: Token::GE,
-                    right: Box::new(AST::IntNumber {token: Token::INT(String::from("1"))}),
+                Box::new(AST::Comparison {
+                    operands: vec![
+                        AST::IntNumber {token: Token::INT(String::from("1")), span: span(0, 1)},
+                        AST::IntNumber {token: Token::INT(String::from("1")), span: span(5, 6)},
+                    ],
+                    operators: vec![Token::GE],
+                    span: span(0, 6),
                 })
-            )}
+            ), span: span(0, 6)}
         );
     }
 
     #[test]
     fn comparison_lt() {
         let mut parser = parser_generator("1 < 1");
-        assert_eq!(parser.parse(),
+        assert_eq!(parse_valid(&mut parser),
             AST::Program { children: vec!(
-                Box::new(AST::BinaryOperation {
-                    left: Box::new(AST::IntNumber {token: Token::INT(String::from("1"))}),
-                    op: Token::LT,
-                    right: Box::new(AST::IntNumber {token: Token::INT(String::from("1"))}),
+                Box::new(AST::Comparison {
+                    operands: vec![
+                        AST::IntNumber {token: Token::INT(String::from("1")), span: span(0, 1)},
+                        AST::IntNumber {token: Token::INT(String::from("1")), span: span(4, 5)},
+                    ],
+                    operators: vec![Token::LT],
+                    span: span(0, 5),
                 })
-            )}
+            ), span: span(0, 5)}
         );
     }
 
     #[test]
     fn comparison_gt() {
         let mut parser = parser_generator("1 > 1");
-        assert_eq!(parser.parse(),
+        assert_eq!(parse_valid(&mut parser),
             AST::Program { children: vec!(
-                Box::new(AST::BinaryOperation {
-                    left: Box::new(AST::IntNumber {token: Token::INT(String::from("1"))}),
-                    op: Token::GT,
-                    right: Box::new(AST::IntNumber {token: Token::INT(String::from("1"))}),
+                Box::new(AST::Comparison {
+                    operands: vec![
+                        AST::IntNumber {token: Token::INT(String::from("1")), span: span(0, 1)},
+                        AST::IntNumber {token: Token::INT(String::from("1")), span: span(4, 5)},
+                    ],
+                    operators: vec![Token::GT],
+                    span: span(0, 5),
+                })
+            ), span: span(0, 5)}
+        );
+    }
+
+    #[test]
+    fn chained_comparison() {
+        let mut parser = parser_generator("1 < a + 1 <= 3");
+        assert_eq!(parse_valid(&mut parser),
+            AST::Program { children: vec!(
+                Box::new(AST::Comparison {
+                    operands: vec![
+                        AST::IntNumber {token: Token::INT(String::from("1")), span: span(0, 1)},
+                        AST::BinaryOperation {
+                            left: Box::new(AST::Variable {id: Token::ID(String::from("a")), span: span(4, 5)}),
+                            op: Token::PLUS,
+                            right: Box::new(AST::IntNumber {token: Token::INT(String::from("1")), span: span(8, 9)}),
+                            span: span(4, 9),
+                        },
+                        AST::IntNumber {token: Token::INT(String::from("3")), span: span(13, 14)},
+                    ],
+                    operators: vec![Token::LT, Token::LE],
+                    span: span(0, 14),
                 })
-            )}
+            ), span: span(0, 14)}
         );
     }
 
     #[test]
     fn term_plus_operation() {
         let mut parser = parser_generator("1 + 1");
-        assert_eq!(parser.parse(),
+        assert_eq!(parse_valid(&mut parser),
             AST::Program { children: vec!(
                 Box::new(AST::BinaryOperation {
-                    left: Box::new(AST::IntNumber {token: Token::INT(String::from("1"))}),
+                    left: Box::new(AST::IntNumber {token: Token::INT(String::from("1")), span: span(0, 1)}),
                     op: Token::PLUS,
-                    right: Box::new(AST::IntNumber {token: Token::INT(String::from("1"))}),
+                    right: Box::new(AST::IntNumber {token: Token::INT(String::from("1")), span: span(4, 5)}),
+                    span: span(0, 5),
                 })
-            )}
+            ), span: span(0, 5)}
         );
     }
 
     #[test]
     fn term_minus_operation() {
         let mut parser = parser_generator("1 - 1");
-        assert_eq!(parser.parse(),
+        assert_eq!(parse_valid(&mut parser),
             AST::Program { children: vec!(
                 Box::new(AST::BinaryOperation {
-                    left: Box::new(AST::IntNumber {token: Token::INT(String::from("1"))}),
+                    left: Box::new(AST::IntNumber {token: Token::INT(String::from("1")), span: span(0, 1)}),
                     op: Token::MINUS,
-                    right: Box::new(AST::IntNumber {token: Token::INT(String::from("1"))}),
+                    right: Box::new(AST::IntNumber {token: Token::INT(String::from("1")), span: span(4, 5)}),
+                    span: span(0, 5),
                 })
-            )}
+            ), span: span(0, 5)}
         );
     }
 
     #[test]
     fn term_mul_operation() {
         let mut parser = parser_generator("1 * 1");
-        assert_eq!(parser.parse(),
+        assert_eq!(parse_valid(&mut parser),
             AST::Program { children: vec!(
                 Box::new(AST::BinaryOperation {
-                    left: Box::new(AST::IntNumber {token: Token::INT(String::from("1"))}),
+                    left: Box::new(AST::IntNumber {token: Token::INT(String::from("1")), span: span(0, 1)}),
                     op: Token::MUL,
-                    right: Box::new(AST::IntNumber {token: Token::INT(String::from("1"))}),
+                    right: Box::new(AST::IntNumber {token: Token::INT(String::from("1")), span: span(4, 5)}),
+                    span: span(0, 5),
                 })
-            )}
+            ), span: span(0, 5)}
         );
     }
 
     #[test]
     fn term_div_operation() {
         let mut parser = parser_generator("1 / 1");
-        assert_eq!(parser.parse(),
+        assert_eq!(parse_valid(&mut parser),
             AST::Program { children: vec!(
                 Box::new(AST::BinaryOperation {
-                    left: Box::new(AST::IntNumber {token: Token::INT(String::from("1"))}),
+                    left: Box::new(AST::IntNumber {token: Token::INT(String::from("1")), span: span(0, 1)}),
                     op: Token::DIV,
-                    right: Box::new(AST::IntNumber {token: Token::INT(String::from("1"))}),
+                    right: Box::new(AST::IntNumber {token: Token::INT(String::from("1")), span: span(4, 5)}),
+                    span: span(0, 5),
+                })
+            ), span: span(0, 5)}
+        );
+    }
+
+    #[test]
+    fn term_floordiv_and_mod_operations() {
+        let mut parser = parser_generator("7 // 2 % 3");
+        assert_eq!(parse_valid(&mut parser),
+            AST::Program { children: vec!(
+                Box::new(AST::BinaryOperation {
+                    left: Box::new(AST::BinaryOperation {
+                        left: Box::new(AST::IntNumber {token: Token::INT(String::from("7")), span: span(0, 1)}),
+                        op: Token::FLOORDIV,
+                        right: Box::new(AST::IntNumber {token: Token::INT(String::from("2")), span: span(5, 6)}),
+                        span: span(0, 6),
+                    }),
+                    op: Token::MOD,
+                    right: Box::new(AST::IntNumber {token: Token::INT(String::from("3")), span: span(9, 10)}),
+                    span: span(0, 10),
                 })
-            )}
+            ), span: span(0, 10)}
+        );
+    }
+
+    #[test]
+    fn power_operation() {
+        let mut parser = parser_generator("-2 ** 3 ** -1");
+        assert_eq!(parse_valid(&mut parser),
+            AST::Program { children: vec!(
+                Box::new(AST::UnaryOperation {
+                    op: Token::MINUS,
+                    right: Box::new(AST::BinaryOperation {
+                        left: Box::new(AST::IntNumber {token: Token::INT(String::from("2")), span: span(1, 2)}),
+                        op: Token::POW,
+                        right: Box::new(AST::BinaryOperation {
+                            left: Box::new(AST::IntNumber {token: Token::INT(String::from("3")), span: span(6, 7)}),
+                            op: Token::POW,
+                            right: Box::new(AST::UnaryOperation {
+                                op: Token::MINUS,
+                                right: Box::new(AST::IntNumber {token: Token::INT(String::from("1")), span: span(12, 13)}),
+                                span: span(11, 13),
+                            }),
+                            span: span(6, 13),
+                        }),
+                        span: span(1, 13),
+                    }),
+                    span: span(0, 13),
+                })
+            ), span: span(0, 13)}
         );
     }
 
     #[test]
     fn atom_int_number() {
         let mut parser = parser_generator("1");
-        assert_eq!(parser.parse(),
+        assert_eq!(parse_valid(&mut parser),
             AST::Program { children: vec!(
-                Box::new(AST::IntNumber {token: Token::INT(String::from("1"))})
-            )}
+                Box::new(AST::IntNumber {token: Token::INT(String::from("1")), span: span(0, 1)})
+            ), span: span(0, 1)}
         );
     }
 
     #[test]
     fn atom_float_number() {
         let mut parser = parser_generator("1.0");
-        assert_eq!(parser.parse(),
+        assert_eq!(parse_valid(&mut parser),
+            AST::Program { children: vec!(
+                Box::new(AST::FloatNumber {token: Token::FLOAT(String::from("1.0")), span: span(0, 3)})
+            ), span: span(0, 3)}
+        );
+    }
+
+    #[test]
+    fn atom_string() {
+        let mut parser = parser_generator("'a'");
+        assert_eq!(parse_valid(&mut parser),
             AST::Program { children: vec!(
-                Box::new(AST::FloatNumber {token: Token::FLOAT(String::from("1.0"))})
-            )}
+                Box::new(AST::String {token: Token::STRING(String::from("a")), span: span(0, 3)})
+            ), span: span(0, 3)}
         );
     }
 
     #[test]
     fn atom_parenthesis() {
         let mut parser = parser_generator("(1)");
-        assert_eq!(parser.parse(),
+        assert_eq!(parse_valid(&mut parser),
             AST::Program { children: vec!(
-                Box::new(AST::IntNumber {token: Token::INT(String::from("1"))})
-            )}
+                Box::new(AST::IntNumber {token: Token::INT(String::from("1")), span: span(1, 2)})
+            ), span: span(1, 2)}
         );
     }
 
     #[test]
     fn atom_plus_unary() {
         let mut parser = parser_generator("+1");
-        assert_eq!(parser.parse(),
+        assert_eq!(parse_valid(&mut parser),
             AST::Program { children: vec!(
                 Box::new(AST::UnaryOperation {
                     op: Token::PLUS,
-                    right: Box::new(AST::IntNumber {token: Token::INT(String::from("1"))})
+                    right: Box::new(AST::IntNumber {token: Token::INT(String::from("1")), span: span(1, 2)}),
+                    span: span(0, 2),
                 })
-            )}
+            ), span: span(0, 2)}
         );
     }
 
     #[test]
     fn atom_minus_unary() {
         let mut parser = parser_generator("-1");
-        assert_eq!(parser.parse(),
+        assert_eq!(parse_valid(&mut parser),
             AST::Program { children: vec!(
                 Box::new(AST::UnaryOperation {
                     op: Token::MINUS,
-                    right: Box::new(AST::IntNumber {token: Token::INT(String::from("1"))})
+                    right: Box::new(AST::IntNumber {token: Token::INT(String::from("1")), span: span(1, 2)}),
+                    span: span(0, 2),
                 })
-            )}
+            ), span: span(0, 2)}
         );
     }
 
     #[test]
     fn atom_boolean_true() {
         let mut parser = parser_generator("true");
-        assert_eq!(parser.parse(),
+        assert_eq!(parse_valid(&mut parser),
             AST::Program { children: vec!(
-                Box::new(AST::Boolean {token: Token::BOOL(true)})
-            )}
+                Box::new(AST::Boolean {token: Token::BOOL(true), span: span(0, 4)})
+            ), span: span(0, 4)}
         );
     }
 
     #[test]
     fn atom_boolean_false() {
         let mut parser = parser_generator("false");
-        assert_eq!(parser.parse(),
+        assert_eq!(parse_valid(&mut parser),
             AST::Program { children: vec!(
-                Box::new(AST::Boolean {token: Token::BOOL(false)})
-            )}
+                Box::new(AST::Boolean {token: Token::BOOL(false), span: span(0, 5)})
+            ), span: span(0, 5)}
         );
     }
 
     #[test]
     fn atom_variable() {
         let mut parser = parser_generator("a");
-        assert_eq!(parser.parse(),
+        assert_eq!(parse_valid(&mut parser),
             AST::Program { children: vec!(
-                Box::new(AST::Variable {id: Token::ID(String::from("a"))})
-            )}
+                Box::new(AST::Variable {id: Token::ID(String::from("a")), span: span(0, 1)})
+            ), span: span(0, 1)}
         );
     }
 
     #[test]
     fn one_newline() {
         let mut parser = parser_generator("\n");
-        assert_eq!(parser.parse(),
-            AST::Program { children: vec!()}
+        assert_eq!(parse_valid(&mut parser),
+            AST::Program { children: vec!(), span: Span::default()}
         );
     }
 
     #[test]
     fn multiple_newlines() {
         let mut parser = parser_generator("\n\n\n");
-        assert_eq!(parser.parse(),
-            AST::Program { children: vec!()}
+        assert_eq!(parse_valid(&mut parser),
+            AST::Program { children: vec!(), span: Span::default()}
         );
     }
 
     #[test]
     fn empty() {
         let mut parser = parser_generator("");
-        assert_eq!(parser.parse(),
-            AST::Program { children: vec!()}
+        assert_eq!(parse_valid(&mut parser),
+            AST::Program { children: vec!(), span: Span::default()}
         );
     }
+
+    #[test]
+    fn nested_spans() {
+        let mut parser = parser_generator("x = 1\nf(x + 2 * y)");
+        let tree = parse_valid(&mut parser);
+        assert_eq!(tree.span(), Span::new(0, 18, 1, 1));
+        match tree {
+            AST::Program {children, ..} => {
+                assert_eq!(children[1].span(), Span::new(6, 18, 2, 1));
+                match *children[1].clone() {
+                    AST::FunctionCall {arguments, ..} => {
+                        assert_eq!(arguments[0].span(), Span::new(8, 17, 2, 3));
+                    },
+                    other => panic!("expected a function call, got {:?}", other),
+                }
+            },
+            other => panic!("expected a program, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn syntax_error() {
+        let mut parser = parser_generator("if true\n    a = 1\n");
+        let (tree, errors) = parser.parse();
+        assert_eq!(errors, vec![
+            BjornError::SyntaxError("expected `:`, found newline".into())
+                .at(span(7, 8))
+                .with_help("add `:` after the `if` condition"),
+        ]);
+        assert_eq!(tree, AST::Program {
+            children: vec![Box::new(AST::Error {span: span(0, 18)})],
+            span: span(0, 18),
+        });
+    }
+
+    #[test]
+    fn multiple_syntax_errors() {
+        let mut parser = parser_generator(
+            "if true\n    a = 1\nb = )\nwhile true:\n    c\n    d =\n§\ne = 1\n"
+        );
+        let (tree, errors) = parser.parse();
+        let kinds: Vec<&str> = errors.iter().map(|error| error.name()).collect();
+        assert_eq!(kinds, vec!["SyntaxError", "SyntaxError", "SyntaxError", "LexicalError"]);
+        let lines: Vec<usize> = errors.iter().map(|error| error.span().unwrap().line).collect();
+        assert_eq!(lines, vec![1, 3, 6, 7]);
+        match tree {
+            AST::Program {children, ..} => {
+                assert_eq!(children.len(), 4);
+                match (&*children[0], &*children[1], &*children[2], &*children[3]) {
+                    (AST::Error {..}, AST::Error {..}, AST::WhileStatement {..}, AST::Assignment {..}) => {},
+                    other => panic!("unexpected tree {:?}", other),
+                }
+            },
+            other => panic!("expected a program, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn unexpected_indent() {
+        let mut parser = parser_generator("a\n    b\n        c\nd\n");
+        let (tree, errors) = parser.parse();
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors[0].name(), "IndentationError");
+        assert_eq!(errors[0].message(), "unexpected indent");
+        match tree {
+            AST::Program {children, ..} => assert_eq!(children.len(), 3),
+            other => panic!("expected a program, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn misspelled_keywords() {
+        let mut parser = parser_generator("whille true:\n    a\nretrun 1\nretry = 1 +\n");
+        let (_, errors) = parser.parse();
+        let helps: Vec<Option<&str>> = errors.iter().map(|error| error.help()).collect();
+        assert_eq!(helps, vec![
+            Some("did you mean `while`?"),
+            Some("did you mean `return`?"),
+            Some("expected an expression"),
+        ]);
+    }
 }
@@ -0,0 +1,47 @@
+use std::fmt;
+
+
+/// Location of a piece of source code.
+///
+/// `start` and `end` are byte offsets in the input,
+/// `line` and `column` (both starting at 1) locate `start`,
+/// the column being counted in graphemes.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct Span {
+    pub start: usize,
+    pub end: usize,
+    pub line: usize,
+    pub column: usize,
+}
+
+impl Span {
+
+    pub fn new(start: usize, end: usize, line: usize, column: usize) -> Span {
+        Span {start: start, end: end, line: line, column: column}
+    }
+
+    /// Span starting where `self` starts and ending where `other` ends.
+    pub fn to(&self, other: Span) -> Span {
+        Span {end: other.end.max(self.start), ..*self}
+    }
+
+    pub fn len(&self) -> usize {
+        self.end - self.start
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.start == self.end
+    }
+}
+
+impl Default for Span {
+    fn default() -> Span {
+        Span::new(0, 0, 1, 1)
+    }
+}
+
+impl fmt::Display for Span {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}:{}", self.line, self.column)
+    }
+}
@@ -0,0 +1,72 @@
+//!
+//! Suggestions of names close to a misspelled one.
+//!
+
+
+/// Number of single character insertions, deletions, substitutions
+/// and transpositions of adjacent characters to turn `a` into `b`,
+/// no substring being edited twice.
+pub fn edit_distance(a: &str, b: &str) -> usize {
+    let a: Vec<char> = a.chars().collect();
+    let b: Vec<char> = b.chars().collect();
+    // Distances from the prefixes of `a` two rows and one row above
+    let mut before_previous: Vec<usize> = Vec::new();
+    let mut previous: Vec<usize> = (0..=b.len()).collect();
+    for (i, ca) in a.iter().enumerate() {
+        let mut current = vec![i + 1];
+        for (j, cb) in b.iter().enumerate() {
+            let substitution = previous[j] + if ca == cb { 0 } else { 1 };
+            let mut distance = substitution.min(previous[j + 1] + 1).min(current[j] + 1);
+            if i > 0 && j > 0 && *ca == b[j - 1] && a[i - 1] == *cb {
+                distance = distance.min(before_previous[j - 1] + 1);
+            }
+            current.push(distance);
+        }
+        before_previous = previous;
+        previous = current;
+    }
+    previous[b.len()]
+}
+
+/// Closest candidate to `name`, if any is close enough to be a typo.
+/// Ties are broken alphabetically so that suggestions are stable.
+pub fn suggest<'a, I>(name: &str, candidates: I) -> Option<&'a str>
+    where I: IntoIterator<Item = &'a str>
+{
+    let threshold = (name.chars().count() / 3).max(1);
+    candidates.into_iter()
+        .filter(|candidate| *candidate != name)
+        .map(|candidate| (edit_distance(name, candidate), candidate))
+        .filter(|(distance, _)| *distance <= threshold)
+        .min()
+        .map(|(_, candidate)| candidate)
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn distances() {
+        assert_eq!(edit_distance("", ""), 0);
+        assert_eq!(edit_distance("abc", ""), 3);
+        assert_eq!(edit_distance("fibonaci", "fibonacci"), 1);
+        assert_eq!(edit_distance("retrun", "return"), 1);
+        assert_eq!(edit_distance("ab", "ba"), 1);
+        assert_eq!(edit_distance("ca", "abc"), 3);
+        assert_eq!(edit_distance("bjørn", "bjorn"), 1);
+    }
+
+    #[test]
+    fn suggestions() {
+        let candidates = ["print", "fibonacci", "while", "len", "a"];
+        assert_eq!(suggest("fibonaci", candidates.iter().cloned()), Some("fibonacci"));
+        assert_eq!(suggest("whille", candidates.iter().cloned()), Some("while"));
+        assert_eq!(suggest("pritn", candidates.iter().cloned()), Some("print"));
+        assert_eq!(suggest("lne", candidates.iter().cloned()), Some("len"));
+        assert_eq!(suggest("b", candidates.iter().cloned()), Some("a"));
+        assert_eq!(suggest("xyz", candidates.iter().cloned()), None);
+        assert_eq!(suggest("print", candidates.iter().cloned()), None);
+    }
+}
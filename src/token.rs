@@ -1,7 +1,16 @@
+use std::fmt;
+
+/// Reserved words of the language.
+pub const KEYWORDS: &[&str] = &[
+    "true", "false", "or", "and", "not", "if", "else", "while", "for", "in", "break", "continue",
+    "def", "return", "lambda", "global", "nonlocal",
+];
+
 #[derive(Debug, PartialEq, Clone)]
 pub enum Token {
     INT(String),
     FLOAT(String),
+    STRING(String),
     BOOL(bool),
     ID(String),
 
@@ -12,9 +21,16 @@ pub enum Token {
     IF,
     ELSE,
     WHILE,
+    FOR,
+    IN,
+    BREAK,
+    CONTINUE,
 
     DEF,
     RETURN,
+    LAMBDA,
+    GLOBAL,
+    NONLOCAL,
 
     COLON,
     COMMA,
@@ -31,35 +47,86 @@ pub enum Token {
     GT,
 
     ASSIGN,
+    PLUSASSIGN,
+    MINUSASSIGN,
+    MULASSIGN,
+    DIVASSIGN,
+    FLOORDIVASSIGN,
+    MODASSIGN,
+    POWASSIGN,
 
     PLUS,
     MINUS,
     MUL,
     DIV,
+    FLOORDIV,
+    MOD,
+    POW,
 
     LPAREN,
     RPAREN,
+    LBRACKET,
+    RBRACKET,
+    LBRACE,
+    RBRACE,
+    DOT,
 
     EOF,
+
+    // Trivia, only produced by a lexer preserving it
+    WHITESPACE(String),
+    COMMENT(String),
+    ERROR(String),
 }
 
 impl Token {
+    /// Whether the token carries no meaning for the program.
+    pub fn is_trivia(&self) -> bool {
+        matches!(self, Token::WHITESPACE(_) | Token::COMMENT(_) | Token::ERROR(_))
+    }
+
+    /// Binary operator of an augmented assignment, such as `+` for `+=`.
+    pub fn augmented_operator(&self) -> Option<Token> {
+        match self {
+            Token::PLUSASSIGN => Some(Token::PLUS),
+            Token::MINUSASSIGN => Some(Token::MINUS),
+            Token::MULASSIGN => Some(Token::MUL),
+            Token::DIVASSIGN => Some(Token::DIV),
+            Token::FLOORDIVASSIGN => Some(Token::FLOORDIV),
+            Token::MODASSIGN => Some(Token::MOD),
+            Token::POWASSIGN => Some(Token::POW),
+            _ => None,
+        }
+    }
+
+    /// Value of an integer literal, which may have a radix prefix
+    /// and underscores between its digits.
+    /// `None` if it does not fit in an `i32`.
     pub fn integer(&self) -> Option<i32> {
         if let Token::INT(i) = self {
-            return match i.parse::<i32>() {
-                Ok(i) => Some(i),
-                Err(_) => None
-            }
+            let digits = i.replace('_', "");
+            let (digits, radix) = match digits.get(..2) {
+                Some("0x") | Some("0X") => (&digits[2..], 16),
+                Some("0o") | Some("0O") => (&digits[2..], 8),
+                Some("0b") | Some("0B") => (&digits[2..], 2),
+                _ => (&digits[..], 10),
+            };
+            return i32::from_str_radix(digits, radix).ok()
         }
         None
     }
 
+    /// Value of a float literal, `None` if it is too large to be represented.
     pub fn float(&self) -> Option<f64> {
         if let Token::FLOAT(i) = self {
-            return match i.parse::<f64>() {
-                Ok(i) => Some(i),
-                Err(_) => None
-            }
+            return i.replace('_', "").parse::<f64>().ok().filter(|f| f.is_finite())
+        }
+        None
+    }
+
+    pub fn string(&self) -> Option<String> {
+        if let Token::STRING(s) = self {
+            return Some(s.clone())
         }
         None
     }
@@ -73,11 +140,83 @@ impl Token {
 
     pub fn identifier(&self) -> Option<String> {
         if let Token::ID(i) = self {
-            return match i.parse::<String>() {
-                Ok(i) => Some(i),
-                Err(_) => None
-            }
+            return i.parse::<String>().ok()
         }
         None
     }
 }
+
+impl fmt::Display for Token {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Token::INT(i) => write!(f, "integer `{}`", i),
+            Token::FLOAT(i) => write!(f, "float `{}`", i),
+            Token::STRING(s) => write!(f, "string {:?}", s),
+            Token::BOOL(i) => write!(f, "`{}`", i),
+            Token::ID(i) => write!(f, "identifier `{}`", i),
+
+            Token::NEWLINE => write!(f, "newline"),
+            Token::INDENT => write!(f, "indent"),
+            Token::DEDENT => write!(f, "dedent"),
+
+            Token::IF => write!(f, "`if`"),
+            Token::ELSE => write!(f, "`else`"),
+            Token::WHILE => write!(f, "`while`"),
+            Token::FOR => write!(f, "`for`"),
+            Token::IN => write!(f, "`in`"),
+            Token::BREAK => write!(f, "`break`"),
+            Token::CONTINUE => write!(f, "`continue`"),
+
+            Token::DEF => write!(f, "`def`"),
+            Token::RETURN => write!(f, "`return`"),
+            Token::LAMBDA => write!(f, "`lambda`"),
+            Token::GLOBAL => write!(f, "`global`"),
+            Token::NONLOCAL => write!(f, "`nonlocal`"),
+
+            Token::COLON => write!(f, "`:`"),
+            Token::COMMA => write!(f, "`,`"),
+
+            Token::OR => write!(f, "`or`"),
+            Token::AND => write!(f, "`and`"),
+            Token::NOT => write!(f, "`not`"),
+
+            Token::EQ => write!(f, "`==`"),
+            Token::NE => write!(f, "`!=`"),
+            Token::LE => write!(f, "`<=`"),
+            Token::GE => write!(f, "`>=`"),
+            Token::LT => write!(f, "`<`"),
+            Token::GT => write!(f, "`>`"),
+
+            Token::ASSIGN => write!(f, "`=`"),
+            Token::PLUSASSIGN => write!(f, "`+=`"),
+            Token::MINUSASSIGN => write!(f, "`-=`"),
+            Token::MULASSIGN => write!(f, "`*=`"),
+            Token::DIVASSIGN => write!(f, "`/=`"),
+            Token::FLOORDIVASSIGN => write!(f, "`//=`"),
+            Token::MODASSIGN => write!(f, "`%=`"),
+            Token::POWASSIGN => write!(f, "`**=`"),
+
+            Token::PLUS => write!(f, "`+`"),
+            Token::MINUS => write!(f, "`-`"),
+            Token::MUL => write!(f, "`*`"),
+            Token::DIV => write!(f, "`/`"),
+            Token::FLOORDIV => write!(f, "`//`"),
+            Token::MOD => write!(f, "`%`"),
+            Token::POW => write!(f, "`**`"),
+
+            Token::LPAREN => write!(f, "`(`"),
+            Token::RPAREN => write!(f, "`)`"),
+            Token::LBRACKET => write!(f, "`[`"),
+            Token::RBRACKET => write!(f, "`]`"),
+            Token::LBRACE => write!(f, "`{{`"),
+            Token::RBRACE => write!(f, "`}}`"),
+            Token::DOT => write!(f, "`.`"),
+
+            Token::EOF => write!(f, "end of file"),
+
+            Token::WHITESPACE(_) => write!(f, "whitespace"),
+            Token::COMMENT(_) => write!(f, "comment"),
+            Token::ERROR(s) => write!(f, "invalid text `{}`", s),
+        }
+    }
+}
@@ -1,40 +1,504 @@
+use unicode_segmentation::UnicodeSegmentation;
+
 use ast::AST;
-use builtins::Callback;
+use builtins::{arity, Callback};
+use dict::Dict;
+use error::BjornError;
+use memory::Environment;
+use token::Token;
 
+use std::cell::RefCell;
 use std::fmt;
+use std::rc::Rc;
 use std::ops::Add;
 use std::ops::Sub;
 use std::ops::Mul;
 use std::ops::Div;
+use std::ops::Rem;
 use std::ops::Neg;
 
 use std::ops::Not;
 
 use std::cmp::Ordering;
 
+/// User function, along with the scope it was defined in.
+#[derive(Debug, Clone)]
+pub struct Function {
+    /// Name it was declared with, `<lambda>` for lambda expressions.
+    pub name: String,
+    pub parameters: Vec<String>,
+    /// Statements executed by a call.
+    pub body: AST,
+    pub environment: Environment,
+}
+
 #[derive(Debug, Clone)]
 pub enum Value {
     Int(i32),
     Float(f64),
     Bool(bool),
-    Function(AST),
+    Str(String),
+    /// Integers from the start up to the stop excluded, by step.
+    Range(i32, i32, i32),
+    /// Mutable sequence, shared by all the names referring to it.
+    List(Rc<RefCell<Vec<Value>>>),
+    /// Immutable sequence.
+    Tuple(Rc<Vec<Value>>),
+    /// Mutable mapping, shared by all the names referring to it.
+    Dict(Rc<RefCell<Dict>>),
+    /// Method of a value, named after the attribute it was read from.
+    Method(Box<Value>, String),
+    Function(Rc<Function>),
     BuiltinFunction(Callback),
     None,
 }
 
 impl Value {
 
-    pub fn to_string(&self) -> String {
+    pub fn list(elements: Vec<Value>) -> Value {
+        Value::List(Rc::new(RefCell::new(elements)))
+    }
+
+    pub fn tuple(elements: Vec<Value>) -> Value {
+        Value::Tuple(Rc::new(elements))
+    }
+
+    pub fn type_name(&self) -> &str {
+        match self {
+            Value::Int(_) => "int",
+            Value::Float(_) => "float",
+            Value::Bool(_) => "bool",
+            Value::Str(_) => "str",
+            Value::Range(..) => "range",
+            Value::List(_) => "list",
+            Value::Tuple(_) => "tuple",
+            Value::Dict(_) => "dict",
+            Value::Method(..) => "method",
+            Value::Function(_) => "function",
+            Value::BuiltinFunction(_) => "builtin function",
+            Value::None => "none",
+        }
+    }
+
+    /// Equality as defined by the language, mixed types are a type error.
+    pub fn equals(&self, other: &Value) -> Result<bool, BjornError> {
+        self.equals_within(other, &mut Vec::new())
+    }
+
+    /// Equality of values found within the pairs of containers being compared.
+    /// A pair met again within itself is considered equal, so that
+    /// comparing containers holding themselves terminates.
+    fn equals_within(&self, other: &Value, comparing: &mut Vec<(usize, usize)>) -> Result<bool, BjornError> {
+        match (self, other) {
+            (Value::Int(a), Value::Int(b)) => Ok(a == b),
+            (Value::Float(a), Value::Float(b)) => Ok(a == b),
+            (Value::Int(a), Value::Float(b)) => Ok(&(*a as f64) == b),
+            (Value::Float(a), Value::Int(b)) => Ok(a == &(*b as f64)),
+            (Value::Bool(a), Value::Bool(b)) => Ok(a == b),
+            (Value::Str(a), Value::Str(b)) => Ok(a == b),
+            (Value::List(a), Value::List(b)) => {
+                let pair = (address(a), address(b));
+                if Rc::ptr_eq(a, b) || comparing.contains(&pair) {
+                    return Ok(true)
+                }
+                comparing.push(pair);
+                let equal = equal_elements(&a.borrow(), &b.borrow(), comparing);
+                comparing.pop();
+                equal
+            },
+            (Value::Tuple(a), Value::Tuple(b)) => equal_elements(a, b, comparing),
+            (Value::Dict(a), Value::Dict(b)) => {
+                let pair = (address(a), address(b));
+                if Rc::ptr_eq(a, b) || comparing.contains(&pair) {
+                    return Ok(true)
+                }
+                comparing.push(pair);
+                let equal = equal_entries(&a.borrow(), &b.borrow(), comparing);
+                comparing.pop();
+                equal
+            },
+            (a, b) => Err(invalid_operation("compare", a, b)),
+        }
+    }
+
+    /// Ordering as defined by the language,
+    /// only numbers and strings (lexicographically) can be ordered.
+    pub fn compare(&self, other: &Value) -> Result<Ordering, BjornError> {
+        let ordering = match (self, other) {
+            (Value::Int(a), Value::Int(b)) => a.partial_cmp(b),
+            (Value::Float(a), Value::Float(b)) => a.partial_cmp(b),
+            (Value::Int(a), Value::Float(b)) => (*a as f64).partial_cmp(b),
+            (Value::Float(a), Value::Int(b)) => a.partial_cmp(&(*b as f64)),
+            (Value::Str(a), Value::Str(b)) => a.partial_cmp(b),
+            (Value::List(a), Value::List(b)) => return compare_elements(&a.borrow(), &b.borrow()),
+            (Value::Tuple(a), Value::Tuple(b)) => return compare_elements(a, b),
+            (a, b) => return Err(invalid_operation("compare", a, b)),
+        };
+        ordering.ok_or_else(|| BjornError::TypeError("cannot order `NaN`".into()))
+    }
+
+    /// Division rounded towards negative infinity, integral for integers.
+    pub fn floor_div(self, other: Value) -> Result<Value, BjornError> {
+        match (self, other) {
+            (Value::Int(_), Value::Int(0)) => Err(division_by_zero("integer division")),
+            (Value::Int(a), Value::Int(b)) => {
+                let quotient = a.checked_div(b).ok_or_else(|| overflow("division"))?;
+                if a % b != 0 && (a < 0) != (b < 0) {
+                    Ok(Value::Int(quotient - 1))
+                } else {
+                    Ok(Value::Int(quotient))
+                }
+            },
+            (a, b) => match (a.float(), b.float()) {
+                (Some(_), Some(0.0)) => Err(division_by_zero("float division")),
+                (Some(a), Some(b)) => Ok(Value::Float(float_div_mod(a, b).0)),
+                _ => Err(invalid_operation("divide", &a, &b)),
+            },
+        }
+    }
+
+    /// Exponentiation, integral for integers raised to a non-negative power.
+    pub fn pow(self, other: Value) -> Result<Value, BjornError> {
+        match (self, other) {
+            (Value::Int(a), Value::Int(b)) if b >= 0 => a.checked_pow(b as u32)
+                .map(Value::Int)
+                .ok_or_else(|| overflow("exponentiation")),
+            (a, b) => match (a.float(), b.float()) {
+                (Some(a), Some(b)) if a == 0.0 && b < 0.0 => Err(division_by_zero("exponentiation")),
+                (Some(a), Some(b)) if a < 0.0 && b.fract() != 0.0 => Err(BjornError::RuntimeError(
+                    "negative number cannot be raised to a fractional power".into()
+                )),
+                (Some(a), Some(b)) => Ok(Value::Float(a.powf(b))),
+                _ => Err(BjornError::TypeError(format!(
+                    "cannot raise `{}` to the power of `{}`", a.type_name(), b.type_name()
+                ).into())),
+            },
+        }
+    }
+
+    /// Element at `index`, negative indices count from the end.
+    pub fn index(&self, index: &Value) -> Result<Value, BjornError> {
+        match self {
+            Value::List(list) => {
+                let list = list.borrow();
+                Ok(list[position(index, list.len())?].clone())
+            },
+            Value::Tuple(tuple) => Ok(tuple[position(index, tuple.len())?].clone()),
+            Value::Str(s) => {
+                let graphemes: Vec<&str> = s.graphemes(true).collect();
+                Ok(Value::Str(graphemes[position(index, graphemes.len())?].to_string()))
+            },
+            Value::Dict(dict) => match dict.borrow().get(index)? {
+                Some(value) => Ok(value.clone()),
+                None => Err(BjornError::RuntimeError(
+                    format!("key {} is not in the dict", index.repr()).into()
+                )),
+            },
+            a => Err(BjornError::TypeError(
+                format!("`{}` is not subscriptable", a.type_name()).into()
+            )),
+        }
+    }
+
+    /// Replace the element at `index`, negative indices count from the end.
+    pub fn set_index(&self, index: &Value, value: Value) -> Result<(), BjornError> {
+        match self {
+            Value::List(list) => {
+                let mut list = list.borrow_mut();
+                let position = position(index, list.len())?;
+                list[position] = value;
+                Ok(())
+            },
+            Value::Dict(dict) => dict.borrow_mut().insert(index.clone(), value),
+            a => Err(BjornError::TypeError(
+                format!("`{}` does not support item assignment", a.type_name()).into()
+            )),
+        }
+    }
+
+    /// Copy of the elements from `start` up to `stop` excluded, by `step`.
+    pub fn slice(&self, start: Option<i32>, stop: Option<i32>, step: Option<i32>) -> Result<Value, BjornError> {
+        match self {
+            Value::List(list) => {
+                let list = list.borrow();
+                let positions = positions(list.len(), start, stop, step)?;
+                Ok(Value::list(positions.into_iter().map(|i| list[i].clone()).collect()))
+            },
+            Value::Tuple(tuple) => {
+                let positions = positions(tuple.len(), start, stop, step)?;
+                Ok(Value::tuple(positions.into_iter().map(|i| tuple[i].clone()).collect()))
+            },
+            Value::Str(s) => {
+                let graphemes: Vec<&str> = s.graphemes(true).collect();
+                let positions = positions(graphemes.len(), start, stop, step)?;
+                Ok(Value::Str(positions.into_iter().map(|i| graphemes[i]).collect()))
+            },
+            a => Err(BjornError::TypeError(
+                format!("`{}` is not subscriptable", a.type_name()).into()
+            )),
+        }
+    }
+
+    /// Whether `item` is one of the elements or keys, or a substring of a string.
+    ///
+    /// Elements are compared with `==`, but an element of a type `item` cannot be
+    /// compared with is not equal to it, as keys of another type are not found.
+    pub fn contains(&self, item: &Value) -> Result<bool, BjornError> {
+        match (self, item) {
+            (Value::Dict(dict), key) => dict.borrow().contains_key(key),
+            (Value::List(list), item) => Ok(contains_equal(&list.borrow(), item)),
+            (Value::Tuple(tuple), item) => Ok(contains_equal(tuple, item)),
+            (Value::Str(s), Value::Str(item)) => Ok(s.contains(item.as_str())),
+            (Value::Range(start, stop, step), Value::Int(i)) => {
+                let (start, stop, step, i) = (*start as i64, *stop as i64, *step as i64, *i as i64);
+                let within = if step > 0 { start <= i && i < stop } else { stop < i && i <= start };
+                Ok(within && (i - start) % step == 0)
+            },
+            (Value::Range(..), Value::Float(f)) => {
+                if f.fract() == 0.0 && *f >= i32::MIN as f64 && *f <= i32::MAX as f64 {
+                    self.contains(&Value::Int(*f as i32))
+                } else {
+                    Ok(false)
+                }
+            },
+            (Value::Range(..), _) => Ok(false),
+            (a, b) => Err(BjornError::TypeError(format!(
+                "cannot look for `{}` in `{}`", b.type_name(), a.type_name()
+            ).into())),
+        }
+    }
+
+    /// Attribute `name`, only methods are supported.
+    pub fn attribute(self, name: &str) -> Result<Value, BjornError> {
+        match (&self, name) {
+            (Value::Dict(_), "keys") | (Value::Dict(_), "values") | (Value::Dict(_), "items") => {
+                Ok(Value::Method(Box::new(self), name.to_string()))
+            },
+            (a, name) => Err(BjornError::TypeError(
+                format!("`{}` has no attribute `{}`", a.type_name(), name).into()
+            )),
+        }
+    }
+
+    /// Call the method `name`, as returned by `attribute`.
+    pub fn call_method(&self, name: &str, arguments: Vec<Value>) -> Result<Value, BjornError> {
+        match (self, name) {
+            (Value::Dict(dict), "keys") => {
+                arity(name, &arguments, 0, 0)?;
+                Ok(Value::list(dict.borrow().keys()))
+            },
+            (Value::Dict(dict), "values") => {
+                arity(name, &arguments, 0, 0)?;
+                Ok(Value::list(dict.borrow().values()))
+            },
+            (Value::Dict(dict), "items") => {
+                arity(name, &arguments, 0, 0)?;
+                let items = dict.borrow().entries().iter()
+                    .map(|(key, value)| Value::tuple(vec![key.clone(), value.clone()]))
+                    .collect();
+                Ok(Value::list(items))
+            },
+            (a, name) => Err(BjornError::TypeError(
+                format!("`{}` has no method `{}`", a.type_name(), name).into()
+            )),
+        }
+    }
+
+    /// Representation within a collection, strings are quoted.
+    pub fn repr(&self) -> String {
         match self {
-            Value::Int(a) => a.to_string(),
-            Value::Float(a) => a.to_string(),
-            Value::Bool(true) => String::from("true"),
-            Value::Bool(false) => String::from("false"),
-            Value::Function(_) => String::from("Function"), // TODO Refactor string representation
-            Value::BuiltinFunction(_) => String::from("BuiltinFunction"), // TODO Refactor string representation
-            Value::None => "".to_string(),
+            Value::Str(s) => format!("{:?}", s),
+            a => a.to_string(),
+        }
+    }
+
+    /// Boolean operand of the logical operator `op`.
+    pub fn boolean(self, op: &Token) -> Result<bool, BjornError> {
+        match self {
+            Value::Bool(a) => Ok(a),
+            a => Err(BjornError::TypeError(
+                format!("cannot apply {} to `{}`", op, a.type_name()).into()
+            )),
+        }
+    }
+
+    /// Numeric value as a float, if any.
+    fn float(&self) -> Option<f64> {
+        match *self {
+            Value::Int(a) => Some(a as f64),
+            Value::Float(a) => Some(a),
+            _ => None,
         }
     }
+
+    /// Values a `for` loop goes through.
+    pub fn iter(&self) -> Result<Box<dyn Iterator<Item = Value>>, BjornError> {
+        match *self {
+            Value::Range(start, stop, step) => {
+                let mut next = Some(start);
+                Ok(Box::new(::std::iter::from_fn(move || {
+                    let current = next.filter(|i| if step > 0 { *i < stop } else { *i > stop })?;
+                    next = current.checked_add(step);
+                    Some(Value::Int(current))
+                })))
+            },
+            Value::List(ref list) => Ok(Box::new(list.borrow().clone().into_iter())),
+            Value::Tuple(ref tuple) => Ok(Box::new(Vec::clone(tuple).into_iter())),
+            Value::Dict(ref dict) => Ok(Box::new(dict.borrow().keys().into_iter())),
+            Value::Str(ref s) => {
+                let graphemes: Vec<Value> = s.graphemes(true)
+                    .map(|grapheme| Value::Str(grapheme.to_string()))
+                    .collect();
+                Ok(Box::new(graphemes.into_iter()))
+            },
+            ref a => Err(BjornError::TypeError(
+                format!("`{}` is not iterable", a.type_name()).into()
+            )),
+        }
+    }
+}
+
+/// Position of `index` in a sequence of `length` elements.
+fn position(index: &Value, length: usize) -> Result<usize, BjornError> {
+    match *index {
+        Value::Int(i) => {
+            let i = if i < 0 { i as i64 + length as i64 } else { i as i64 };
+            if i < 0 || i >= length as i64 {
+                return Err(BjornError::RuntimeError("index out of range".into()))
+            }
+            Ok(i as usize)
+        },
+        ref a => Err(BjornError::TypeError(
+            format!("indices must be `int`, not `{}`", a.type_name()).into()
+        )),
+    }
+}
+
+/// Positions of a slice in a sequence of `length` elements,
+/// out of range bounds are clamped.
+fn positions(length: usize, start: Option<i32>, stop: Option<i32>, step: Option<i32>) -> Result<Vec<usize>, BjornError> {
+    let step = step.unwrap_or(1) as i64;
+    if step == 0 {
+        return Err(BjornError::RuntimeError("slice step must not be zero".into()))
+    }
+    let length = length as i64;
+    let (lowest, highest) = if step > 0 { (0, length) } else { (-1, length - 1) };
+    let clamp = |bound: Option<i32>, default: i64| match bound {
+        None => default,
+        Some(bound) if bound < 0 => (bound as i64 + length).max(lowest),
+        Some(bound) => (bound as i64).min(highest),
+    };
+    let (mut i, stop) = if step > 0 {
+        (clamp(start, 0), clamp(stop, length))
+    } else {
+        (clamp(start, length - 1), clamp(stop, -1))
+    };
+    let mut positions = Vec::new();
+    while (step > 0 && i < stop) || (step < 0 && i > stop) {
+        positions.push(i as usize);
+        i += step;
+    }
+    Ok(positions)
+}
+
+/// Floor division and remainder of floats, computed together so that
+/// `a == (a // b) * b + a % b` as closely as rounding allows.
+/// The remainder has the sign of the divisor.
+fn float_div_mod(a: f64, b: f64) -> (f64, f64) {
+    let mut remainder = a % b;
+    // `a - remainder` is a multiple of `b`, only the division may round
+    let mut quotient = (a - remainder) / b;
+    if remainder != 0.0 && (remainder < 0.0) != (b < 0.0) {
+        remainder += b;
+        quotient -= 1.0;
+    }
+    if remainder == 0.0 {
+        remainder = 0.0_f64.copysign(b);
+    }
+    let quotient = if quotient == 0.0 {
+        0.0_f64.copysign(a / b)
+    } else {
+        quotient.round()
+    };
+    (quotient, remainder)
+}
+
+/// Element-wise equality of two sequences.
+fn equal_elements(a: &[Value], b: &[Value], comparing: &mut Vec<(usize, usize)>) -> Result<bool, BjornError> {
+    if a.len() != b.len() {
+        return Ok(false)
+    }
+    for (x, y) in a.iter().zip(b.iter()) {
+        if !x.equals_within(y, comparing)? {
+            return Ok(false)
+        }
+    }
+    Ok(true)
+}
+
+/// Whether one of the `elements` equals `item`, elements that cannot be compared with it being different.
+fn contains_equal(elements: &[Value], item: &Value) -> bool {
+    elements.iter().any(|element| element.equals(item).unwrap_or(false))
+}
+
+/// Equality of two dicts, whatever the order of their keys.
+fn equal_entries(a: &Dict, b: &Dict, comparing: &mut Vec<(usize, usize)>) -> Result<bool, BjornError> {
+    if a.len() != b.len() {
+        return Ok(false)
+    }
+    for (key, x) in a.entries() {
+        match b.get(key)? {
+            Some(y) if x.equals_within(y, comparing)? => {},
+            _ => return Ok(false),
+        }
+    }
+    Ok(true)
+}
+
+/// Identity of a shared container.
+fn address<T>(container: &Rc<T>) -> usize {
+    Rc::as_ptr(container) as *const () as usize
+}
+
+thread_local! {
+    /// Containers being displayed, a container met again within itself is shown as `[...]` or `{...}`.
+    static DISPLAYING: RefCell<Vec<usize>> = const { RefCell::new(Vec::new()) };
+}
+
+/// Display a container with `display`, or `placeholder` if it is already being displayed.
+fn display_once<F>(f: &mut fmt::Formatter, container: usize, placeholder: &str, display: F) -> fmt::Result
+    where F: FnOnce(&mut fmt::Formatter) -> fmt::Result
+{
+    let entered = DISPLAYING.with(|displaying| {
+        let mut displaying = displaying.borrow_mut();
+        if displaying.contains(&container) {
+            return false
+        }
+        displaying.push(container);
+        true
+    });
+    if !entered {
+        return write!(f, "{}", placeholder)
+    }
+    let result = display(f);
+    DISPLAYING.with(|displaying| displaying.borrow_mut().pop());
+    result
+}
+
+/// Lexicographic ordering of two sequences, by the first elements that differ.
+fn compare_elements(a: &[Value], b: &[Value]) -> Result<Ordering, BjornError> {
+    for (x, y) in a.iter().zip(b.iter()) {
+        if !x.equals(y)? {
+            return x.compare(y)
+        }
+    }
+    Ok(a.len().cmp(&b.len()))
+}
+
+fn invalid_operation(operation: &str, a: &Value, b: &Value) -> BjornError {
+    BjornError::TypeError(format!(
+        "cannot {} `{}` and `{}`", operation, a.type_name(), b.type_name()
+    ).into())
 }
 
 impl fmt::Display for Value {
@@ -44,7 +508,27 @@ impl fmt::Display for Value {
             Value::Float(a) => write!(f, "{}", a),
             Value::Bool(true) => write!(f, "true"),
             Value::Bool(false) => write!(f, "false"),
-            Value::Function(_) => write!(f, "Function"), // TODO Refactor string representation
+            Value::Str(a) => write!(f, "{}", a),
+            Value::Range(start, stop, 1) => write!(f, "range({}, {})", start, stop),
+            Value::Range(start, stop, step) => write!(f, "range({}, {}, {})", start, stop, step),
+            Value::List(list) => display_once(f, address(list), "[...]", |f| {
+                let elements: Vec<String> = list.borrow().iter().map(Value::repr).collect();
+                write!(f, "[{}]", elements.join(", "))
+            }),
+            // A single element is followed by a comma, as in the literal
+            Value::Tuple(tuple) if tuple.len() == 1 => write!(f, "({},)", tuple[0].repr()),
+            Value::Tuple(tuple) => {
+                let elements: Vec<String> = tuple.iter().map(Value::repr).collect();
+                write!(f, "({})", elements.join(", "))
+            },
+            Value::Dict(dict) => display_once(f, address(dict), "{...}", |f| {
+                let entries: Vec<String> = dict.borrow().entries().iter()
+                    .map(|(key, value)| format!("{}: {}", key.repr(), value.repr()))
+                    .collect();
+                write!(f, "{{{}}}", entries.join(", "))
+            }),
+            Value::Method(value, name) => write!(f, "<method {}.{}>", value.type_name(), name),
+            Value::Function(function) => write!(f, "<function {}>", function.name),
             Value::BuiltinFunction(_) => write!(f, "BuiltinFunction"), // TODO Refactor string representation
             Value::None => write!(f, ""),
         }
@@ -53,106 +537,156 @@ impl fmt::Display for Value {
 }
 
 impl Add for Value {
-    type Output = Value;
+    type Output = Result<Value, BjornError>;
 
-    fn add(self, other: Value) -> Value {
+    fn add(self, other: Value) -> Result<Value, BjornError> {
         match (self, other) {
-            (Value::Int(a), Value::Int(b)) => Value::Int(a + b),
-            (Value::Float(a), Value::Float(b)) => Value::Float(a + b),
-            (Value::Int(a), Value::Float(b)) => Value::Float(a as f64 + b),
-            (Value::Float(a), Value::Int(b)) => Value::Float(a + b as f64),
-            (_, _) => panic!("Invalid operation."),
+            (Value::Int(a), Value::Int(b)) => a.checked_add(b)
+                .map(Value::Int)
+                .ok_or_else(|| overflow("addition")),
+            (Value::Float(a), Value::Float(b)) => Ok(Value::Float(a + b)),
+            (Value::Int(a), Value::Float(b)) => Ok(Value::Float(a as f64 + b)),
+            (Value::Float(a), Value::Int(b)) => Ok(Value::Float(a + b as f64)),
+            (Value::Str(a), Value::Str(b)) => Ok(Value::Str(a + &b)),
+            (Value::List(a), Value::List(b)) => {
+                let mut elements = a.borrow().clone();
+                elements.extend(b.borrow().iter().cloned());
+                Ok(Value::list(elements))
+            },
+            (Value::Tuple(a), Value::Tuple(b)) => {
+                let mut elements = a.to_vec();
+                elements.extend(b.iter().cloned());
+                Ok(Value::tuple(elements))
+            },
+            (a, b) => Err(invalid_operation("add", &a, &b)),
         }
     }
 }
 
 impl Sub for Value {
-    type Output = Value;
+    type Output = Result<Value, BjornError>;
 
-    fn sub(self, other: Value) -> Value {
+    fn sub(self, other: Value) -> Result<Value, BjornError> {
         match (self, other) {
-            (Value::Int(a), Value::Int(b)) => Value::Int(a - b),
-            (Value::Float(a), Value::Float(b)) => Value::Float(a - b),
-            (Value::Int(a), Value::Float(b)) => Value::Float(a as f64 - b),
-            (Value::Float(a), Value::Int(b)) => Value::Float(a - b as f64),
-            (_, _) => panic!("Invalid operation."),
+            (Value::Int(a), Value::Int(b)) => a.checked_sub(b)
+                .map(Value::Int)
+                .ok_or_else(|| overflow("subtraction")),
+            (Value::Float(a), Value::Float(b)) => Ok(Value::Float(a - b)),
+            (Value::Int(a), Value::Float(b)) => Ok(Value::Float(a as f64 - b)),
+            (Value::Float(a), Value::Int(b)) => Ok(Value::Float(a - b as f64)),
+            (a, b) => Err(invalid_operation("subtract", &a, &b)),
         }
     }
 }
 
 impl Mul for Value {
-    type Output = Value;
+    type Output = Result<Value, BjornError>;
 
-    fn mul(self, other: Value) -> Value {
+    fn mul(self, other: Value) -> Result<Value, BjornError> {
         match (self, other) {
-            (Value::Int(a), Value::Int(b)) => Value::Int(a * b),
-            (Value::Float(a), Value::Float(b)) => Value::Float(a * b),
-            (Value::Int(a), Value::Float(b)) => Value::Float(a as f64 * b),
-            (Value::Float(a), Value::Int(b)) => Value::Float(a * b as f64),
-            (_, _) => panic!("Invalid operation."),
+            (Value::Int(a), Value::Int(b)) => a.checked_mul(b)
+                .map(Value::Int)
+                .ok_or_else(|| overflow("multiplication")),
+            (Value::Float(a), Value::Float(b)) => Ok(Value::Float(a * b)),
+            (Value::Int(a), Value::Float(b)) => Ok(Value::Float(a as f64 * b)),
+            (Value::Float(a), Value::Int(b)) => Ok(Value::Float(a * b as f64)),
+            (Value::Str(a), Value::Int(b)) | (Value::Int(b), Value::Str(a)) => repeat(&a, b),
+            (a, b) => Err(invalid_operation("multiply", &a, &b)),
         }
     }
 }
 
 impl Div for Value {
-    type Output = Value;
+    type Output = Result<Value, BjornError>;
+
+    fn div(self, other: Value) -> Result<Value, BjornError> {
+        match (self.float(), other.float()) {
+            (Some(_), Some(0.0)) => Err(division_by_zero("division")),
+            (Some(a), Some(b)) => Ok(Value::Float(a / b)),
+            _ => Err(invalid_operation("divide", &self, &other)),
+        }
+    }
+}
+
+impl Rem for Value {
+    type Output = Result<Value, BjornError>;
 
-    fn div(self, other: Value) -> Value {
+    /// Remainder of the floor division, it has the sign of the divisor.
+    fn rem(self, other: Value) -> Result<Value, BjornError> {
         match (self, other) {
-            (Value::Int(a), Value::Int(b)) => Value::Float(a as f64 / b as f64),
-            (Value::Float(a), Value::Float(b)) => Value::Float(a / b),
-            (Value::Int(a), Value::Float(b)) => Value::Float(a as f64 / b),
-            (Value::Float(a), Value::Int(b)) => Value::Float(a / b as f64),
-            (_, _) => panic!("Invalid operation."),
+            (Value::Int(_), Value::Int(0)) => Err(division_by_zero("modulo")),
+            (Value::Int(a), Value::Int(b)) => {
+                let remainder = a.wrapping_rem(b);
+                if remainder != 0 && (remainder < 0) != (b < 0) {
+                    Ok(Value::Int(remainder + b))
+                } else {
+                    Ok(Value::Int(remainder))
+                }
+            },
+            (a, b) => match (a.float(), b.float()) {
+                (Some(_), Some(0.0)) => Err(division_by_zero("modulo")),
+                (Some(a), Some(b)) => Ok(Value::Float(float_div_mod(a, b).1)),
+                _ => Err(invalid_operation("take the remainder of", &a, &b)),
+            },
         }
     }
 }
 
 impl Neg for Value {
-    type Output = Value;
+    type Output = Result<Value, BjornError>;
 
-    fn neg(self) -> Value {
+    fn neg(self) -> Result<Value, BjornError> {
         match self {
-            Value::Int(a) => Value::Int(-a),
-            Value::Float(a) => Value::Float(-a),
-            _ => panic!("Invalid operation."),
+            Value::Int(a) => a.checked_neg()
+                .map(Value::Int)
+                .ok_or_else(|| overflow("negation")),
+            Value::Float(a) => Ok(Value::Float(-a)),
+            a => Err(BjornError::TypeError(
+                format!("cannot negate `{}`", a.type_name()).into()
+            )),
         }
     }
 }
 
+/// Repetition of a string, a negative count gives an empty string.
+fn repeat(string: &str, count: i32) -> Result<Value, BjornError> {
+    let count = count.max(0) as usize;
+    match string.len().checked_mul(count) {
+        Some(length) if length <= i32::MAX as usize => Ok(Value::Str(string.repeat(count))),
+        _ => Err(BjornError::RuntimeError("repeated string is too long".into())),
+    }
+}
+
+fn division_by_zero(operation: &str) -> BjornError {
+    BjornError::RuntimeError(format!("{} by zero", operation).into())
+}
+
+fn overflow(operation: &str) -> BjornError {
+    BjornError::RuntimeError(format!("integer overflow in {}", operation).into())
+}
+
 impl PartialEq for Value {
     fn eq(&self, other: &Value) -> bool {
-        match (self, other) {
-            (Value::Int(a), Value::Int(b)) => a == b,
-            (Value::Float(a), Value::Float(b)) => a == b,
-            (Value::Int(a), Value::Float(b)) => &(*a as f64) == b,
-            (Value::Float(a), Value::Int(b)) => a == &(*b as f64),
-            (Value::Bool(a), Value::Bool(b)) => a == b,
-            (_, _) => panic!("Invalid operation."),
-        }
+        self.equals(other).unwrap_or(false)
     }
 }
 
 impl PartialOrd for Value {
 
     fn partial_cmp(&self, other: &Value) -> Option<Ordering> {
-        match (self, other) {
-            (Value::Int(a), Value::Int(b)) => a.partial_cmp(b),
-            (Value::Float(a), Value::Float(b)) => a.partial_cmp(b),
-            (Value::Int(a), Value::Float(b)) => (*a as f64).partial_cmp(b),
-            (Value::Float(a), Value::Int(b)) => a.partial_cmp(&(*b as f64)),
-            (_, _) => panic!("Invalid operation."),
-        }
+        self.compare(other).ok()
     }
 }
 
 impl Not for Value {
-    type Output = Value;
+    type Output = Result<Value, BjornError>;
 
-    fn not(self) -> Value {
+    fn not(self) -> Result<Value, BjornError> {
         match self {
-            Value::Bool(a) => Value::Bool(!a),
-            _ => panic!("Invalid operation."),
+            Value::Bool(a) => Ok(Value::Bool(!a)),
+            a => Err(BjornError::TypeError(
+                format!("cannot apply `not` to `{}`", a.type_name()).into()
+            )),
         }
     }
 }
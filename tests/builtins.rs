@@ -1,13 +1,46 @@
 extern crate bjorn;
 
+use bjorn::BjornError;
+
 #[test]
 fn builtin_print() {
-    bjorn::interpret("print(1)");
+    assert_eq!(bjorn::interpret("print(1)"), Ok(String::from("")));
 }
 
 #[test]
-#[should_panic]
 fn builtin_print_wrong_parameters() {
-    bjorn::interpret("print()");
-    bjorn::interpret("print(1, 2)");
+    for input in &["print()", "print(1, 2)"] {
+        match bjorn::interpret(input) {
+            Err(BjornError::ArityError(..)) => {},
+            other => panic!("expected an arity error for `{}`, got {:?}", input, other),
+        }
+    }
+}
+
+#[test]
+fn builtin_range() {
+    assert_eq!(bjorn::interpret("range(3)"), Ok(String::from("range(0, 3)")));
+    assert_eq!(bjorn::interpret("range(1, 9, 2)"), Ok(String::from("range(1, 9, 2)")));
+}
+
+#[test]
+fn builtin_range_wrong_parameters() {
+    for input in &["range()", "range(1, 2, 3, 4)"] {
+        match bjorn::interpret(input) {
+            Err(BjornError::ArityError(..)) => {},
+            other => panic!("expected an arity error for `{}`, got {:?}", input, other),
+        }
+    }
+    match bjorn::interpret("range(1.5)") {
+        Err(BjornError::TypeError(..)) => {},
+        other => panic!("expected a type error, got {:?}", other),
+    }
+    match bjorn::interpret("range(1, 2, 0)") {
+        Err(BjornError::RuntimeError(..)) => {},
+        other => panic!("expected a runtime error, got {:?}", other),
+    }
+    match bjorn::interpret("for i in 1:\n    i") {
+        Err(BjornError::TypeError(..)) => {},
+        other => panic!("expected a type error, got {:?}", other),
+    }
 }
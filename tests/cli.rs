@@ -0,0 +1,43 @@
+use std::env;
+use std::fs;
+use std::process::{self, Command};
+
+/// Errors reported by the command line interface for the source file `input`, one per line.
+fn reported_errors(name: &str, input: &str) -> Vec<String> {
+    let path = env::temp_dir().join(format!("bjorn-cli-{}-{}.bj", name, process::id()));
+    fs::write(&path, input).unwrap();
+    let output = Command::new(env!("CARGO_BIN_EXE_bjorn"))
+        .arg(&path)
+        .args(["--error-format", "json"])
+        .output()
+        .unwrap();
+    fs::remove_file(&path).unwrap();
+    String::from_utf8(output.stderr).unwrap()
+        .lines()
+        .filter(|line| line.starts_with("{\"code\":\"E"))
+        .map(String::from)
+        .collect()
+}
+
+#[test]
+fn single_indentation_error() {
+    for (name, input) in &[
+        ("dedent", "if true:\n    a = 1\n  b = 2\n"),
+        ("tabs", "if true:\n    a = 1\n\tb = 2\n"),
+        ("first-line", "    a = 1\nb = 2\n"),
+        ("indent", "a = 1\n    b = 2\n"),
+    ] {
+        let errors = reported_errors(name, input);
+        assert_eq!(errors.len(), 1, "expected a single error for {:?}, got {:?}", input, errors);
+        assert!(errors[0].contains("\"kind\":\"IndentationError\""), "{}", errors[0]);
+    }
+}
+
+#[test]
+fn single_unterminated_string_error() {
+    for (name, input) in &[("single", "print('abc\nx = 1\n"), ("triple", "print(\"\"\"abc\nx = 1\n")] {
+        let errors = reported_errors(name, input);
+        assert_eq!(errors.len(), 1, "expected a single error for {:?}, got {:?}", input, errors);
+        assert!(errors[0].contains("\"kind\":\"LexicalError\""), "{}", errors[0]);
+    }
+}
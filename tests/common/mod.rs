@@ -0,0 +1,36 @@
+//!
+//! Assertions shared by the integration tests.
+//!
+
+// Each test crate only uses some of the assertions.
+#![allow(dead_code)]
+
+use bjorn::BjornError;
+
+pub fn assert_type_error(input: &str) {
+    match bjorn::interpret(input) {
+        Err(BjornError::TypeError(..)) => {},
+        other => panic!("expected a type error for `{}`, got {:?}", input, other),
+    }
+}
+
+pub fn assert_runtime_error(input: &str) {
+    match bjorn::interpret(input) {
+        Err(BjornError::RuntimeError(..)) => {},
+        other => panic!("expected a runtime error for `{}`, got {:?}", input, other),
+    }
+}
+
+pub fn assert_syntax_error(input: &str) {
+    match bjorn::interpret(input) {
+        Err(BjornError::SyntaxError(..)) => {},
+        other => panic!("expected a syntax error for `{}`, got {:?}", input, other),
+    }
+}
+
+pub fn assert_arity_error(input: &str) {
+    match bjorn::interpret(input) {
+        Err(BjornError::ArityError(..)) => {},
+        other => panic!("expected an arity error for `{}`, got {:?}", input, other),
+    }
+}
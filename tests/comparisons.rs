@@ -1,107 +1,130 @@
 extern crate bjorn;
 
+mod common;
+
+use common::assert_type_error;
+
 #[test]
 fn valid_comparisons() {
     // Equal
-    assert_eq!(bjorn::interpret("1 == 1"), String::from("true"));
-    assert_eq!(bjorn::interpret("1 == 2"), String::from("false"));
-    assert_eq!(bjorn::interpret("1.0 == 1"), String::from("true"));
-    assert_eq!(bjorn::interpret("1 == 1.0"), String::from("true"));
-    assert_eq!(bjorn::interpret("1.0 == 2"), String::from("false"));
-    assert_eq!(bjorn::interpret("1 == 2.0"), String::from("false"));
-
-    assert_eq!(bjorn::interpret("true == true"), String::from("true"));
-    assert_eq!(bjorn::interpret("false == true"), String::from("false"));
-    assert_eq!(bjorn::interpret("true == false"), String::from("false"));
-    assert_eq!(bjorn::interpret("false == false"), String::from("true"));
+    assert_eq!(bjorn::interpret("1 == 1"), Ok(String::from("true")));
+    assert_eq!(bjorn::interpret("1 == 2"), Ok(String::from("false")));
+    assert_eq!(bjorn::interpret("1.0 == 1"), Ok(String::from("true")));
+    assert_eq!(bjorn::interpret("1 == 1.0"), Ok(String::from("true")));
+    assert_eq!(bjorn::interpret("1.0 == 2"), Ok(String::from("false")));
+    assert_eq!(bjorn::interpret("1 == 2.0"), Ok(String::from("false")));
+
+    assert_eq!(bjorn::interpret("true == true"), Ok(String::from("true")));
+    assert_eq!(bjorn::interpret("false == true"), Ok(String::from("false")));
+    assert_eq!(bjorn::interpret("true == false"), Ok(String::from("false")));
+    assert_eq!(bjorn::interpret("false == false"), Ok(String::from("true")));
 
     // Not Equal
-    assert_eq!(bjorn::interpret("1 != 1"), String::from("false"));
-    assert_eq!(bjorn::interpret("1 != 2"), String::from("true"));
-    assert_eq!(bjorn::interpret("1.0 != 1"), String::from("false"));
-    assert_eq!(bjorn::interpret("1 != 1.0"), String::from("false"));
-    assert_eq!(bjorn::interpret("1.0 != 2"), String::from("true"));
-    assert_eq!(bjorn::interpret("1 != 2.0"), String::from("true"));
-
-    assert_eq!(bjorn::interpret("true != true"), String::from("false"));
-    assert_eq!(bjorn::interpret("false != true"), String::from("true"));
-    assert_eq!(bjorn::interpret("true != false"), String::from("true"));
-    assert_eq!(bjorn::interpret("false != false"), String::from("false"));
+    assert_eq!(bjorn::interpret("1 != 1"), Ok(String::from("false")));
+    assert_eq!(bjorn::interpret("1 != 2"), Ok(String::from("true")));
+    assert_eq!(bjorn::interpret("1.0 != 1"), Ok(String::from("false")));
+    assert_eq!(bjorn::interpret("1 != 1.0"), Ok(String::from("false")));
+    assert_eq!(bjorn::interpret("1.0 != 2"), Ok(String::from("true")));
+    assert_eq!(bjorn::interpret("1 != 2.0"), Ok(String::from("true")));
+
+    assert_eq!(bjorn::interpret("true != true"), Ok(String::from("false")));
+    assert_eq!(bjorn::interpret("false != true"), Ok(String::from("true")));
+    assert_eq!(bjorn::interpret("true != false"), Ok(String::from("true")));
+    assert_eq!(bjorn::interpret("false != false"), Ok(String::from("false")));
 
     // Lower or Equal
-    assert_eq!(bjorn::interpret("1 <= 2"), String::from("true"));
-    assert_eq!(bjorn::interpret("1 <= 1"), String::from("true"));
-    assert_eq!(bjorn::interpret("2 <= 1"), String::from("false"));
-    assert_eq!(bjorn::interpret("1.0 <= 2"), String::from("true"));
-    assert_eq!(bjorn::interpret("1.0 <= 1"), String::from("true"));
-    assert_eq!(bjorn::interpret("2.0 <= 1"), String::from("false"));
-    assert_eq!(bjorn::interpret("1 <= 2.0"), String::from("true"));
-    assert_eq!(bjorn::interpret("1 <= 1.0"), String::from("true"));
-    assert_eq!(bjorn::interpret("2 <= 1.0"), String::from("false"));
-    assert_eq!(bjorn::interpret("1.0 <= 2.0"), String::from("true"));
-    assert_eq!(bjorn::interpret("1.0 <= 1.0"), String::from("true"));
-    assert_eq!(bjorn::interpret("2.0 <= 1.0"), String::from("false"));
+    assert_eq!(bjorn::interpret("1 <= 2"), Ok(String::from("true")));
+    assert_eq!(bjorn::interpret("1 <= 1"), Ok(String::from("true")));
+    assert_eq!(bjorn::interpret("2 <= 1"), Ok(String::from("false")));
+    assert_eq!(bjorn::interpret("1.0 <= 2"), Ok(String::from("true")));
+    assert_eq!(bjorn::interpret("1.0 <= 1"), Ok(String::from("true")));
+    assert_eq!(bjorn::interpret("2.0 <= 1"), Ok(String::from("false")));
+    assert_eq!(bjorn::interpret("1 <= 2.0"), Ok(String::from("true")));
+    assert_eq!(bjorn::interpret("1 <= 1.0"), Ok(String::from("true")));
+    assert_eq!(bjorn::interpret("2 <= 1.0"), Ok(String::from("false")));
+    assert_eq!(bjorn::interpret("1.0 <= 2.0"), Ok(String::from("true")));
+    assert_eq!(bjorn::interpret("1.0 <= 1.0"), Ok(String::from("true")));
+    assert_eq!(bjorn::interpret("2.0 <= 1.0"), Ok(String::from("false")));
 
     // Greater or Equal
-    assert_eq!(bjorn::interpret("2 >= 1"), String::from("true"));
-    assert_eq!(bjorn::interpret("1 >= 1"), String::from("true"));
-    assert_eq!(bjorn::interpret("1 >= 2"), String::from("false"));
-    assert_eq!(bjorn::interpret("2.0 >= 1"), String::from("true"));
-    assert_eq!(bjorn::interpret("1.0 >= 1"), String::from("true"));
-    assert_eq!(bjorn::interpret("1.0 >= 2"), String::from("false"));
-    assert_eq!(bjorn::interpret("2 >= 1.0"), String::from("true"));
-    assert_eq!(bjorn::interpret("1 >= 1.0"), String::from("true"));
-    assert_eq!(bjorn::interpret("1 >= 2.0"), String::from("false"));
-    assert_eq!(bjorn::interpret("2.0 >= 1.0"), String::from("true"));
-    assert_eq!(bjorn::interpret("1.0 >= 1.0"), String::from("true"));
-    assert_eq!(bjorn::interpret("1.0 >= 2.0"), String::from("false"));
+    assert_eq!(bjorn::interpret("2 >= 1"), Ok(String::from("true")));
+    assert_eq!(bjorn::interpret("1 >= 1"), Ok(String::from("true")));
+    assert_eq!(bjorn::interpret("1 >= 2"), Ok(String::from("false")));
+    assert_eq!(bjorn::interpret("2.0 >= 1"), Ok(String::from("true")));
+    assert_eq!(bjorn::interpret("1.0 >= 1"), Ok(String::from("true")));
+    assert_eq!(bjorn::interpret("1.0 >= 2"), Ok(String::from("false")));
+    assert_eq!(bjorn::interpret("2 >= 1.0"), Ok(String::from("true")));
+    assert_eq!(bjorn::interpret("1 >= 1.0"), Ok(String::from("true")));
+    assert_eq!(bjorn::interpret("1 >= 2.0"), Ok(String::from("false")));
+    assert_eq!(bjorn::interpret("2.0 >= 1.0"), Ok(String::from("true")));
+    assert_eq!(bjorn::interpret("1.0 >= 1.0"), Ok(String::from("true")));
+    assert_eq!(bjorn::interpret("1.0 >= 2.0"), Ok(String::from("false")));
 
     // Lower Than
-    assert_eq!(bjorn::interpret("1 < 2"), String::from("true"));
-    assert_eq!(bjorn::interpret("1 < 1"), String::from("false"));
-    assert_eq!(bjorn::interpret("2 < 1"), String::from("false"));
-    assert_eq!(bjorn::interpret("1.0 < 2"), String::from("true"));
-    assert_eq!(bjorn::interpret("1.0 < 1"), String::from("false"));
-    assert_eq!(bjorn::interpret("2.0 < 1"), String::from("false"));
-    assert_eq!(bjorn::interpret("1 < 2.0"), String::from("true"));
-    assert_eq!(bjorn::interpret("1 < 1.0"), String::from("false"));
-    assert_eq!(bjorn::interpret("2 < 1.0"), String::from("false"));
-    assert_eq!(bjorn::interpret("1.0 < 2.0"), String::from("true"));
-    assert_eq!(bjorn::interpret("1.0 < 1.0"), String::from("false"));
-    assert_eq!(bjorn::interpret("2.0 < 1.0"), String::from("false"));
+    assert_eq!(bjorn::interpret("1 < 2"), Ok(String::from("true")));
+    assert_eq!(bjorn::interpret("1 < 1"), Ok(String::from("false")));
+    assert_eq!(bjorn::interpret("2 < 1"), Ok(String::from("false")));
+    assert_eq!(bjorn::interpret("1.0 < 2"), Ok(String::from("true")));
+    assert_eq!(bjorn::interpret("1.0 < 1"), Ok(String::from("false")));
+    assert_eq!(bjorn::interpret("2.0 < 1"), Ok(String::from("false")));
+    assert_eq!(bjorn::interpret("1 < 2.0"), Ok(String::from("true")));
+    assert_eq!(bjorn::interpret("1 < 1.0"), Ok(String::from("false")));
+    assert_eq!(bjorn::interpret("2 < 1.0"), Ok(String::from("false")));
+    assert_eq!(bjorn::interpret("1.0 < 2.0"), Ok(String::from("true")));
+    assert_eq!(bjorn::interpret("1.0 < 1.0"), Ok(String::from("false")));
+    assert_eq!(bjorn::interpret("2.0 < 1.0"), Ok(String::from("false")));
 
     // Greater Than
-    assert_eq!(bjorn::interpret("2 > 1"), String::from("true"));
-    assert_eq!(bjorn::interpret("1 > 1"), String::from("false"));
-    assert_eq!(bjorn::interpret("1 > 2"), String::from("false"));
-    assert_eq!(bjorn::interpret("1.0 > 2"), String::from("false"));
-    assert_eq!(bjorn::interpret("1.0 > 1"), String::from("false"));
-    assert_eq!(bjorn::interpret("2.0 > 1"), String::from("true"));
-    assert_eq!(bjorn::interpret("1 > 2.0"), String::from("false"));
-    assert_eq!(bjorn::interpret("1 > 1.0"), String::from("false"));
-    assert_eq!(bjorn::interpret("2 > 1.0"), String::from("true"));
-    assert_eq!(bjorn::interpret("1.0 > 2.0"), String::from("false"));
-    assert_eq!(bjorn::interpret("1.0 > 1.0"), String::from("false"));
-    assert_eq!(bjorn::interpret("2.0 > 1.0"), String::from("true"));
+    assert_eq!(bjorn::interpret("2 > 1"), Ok(String::from("true")));
+    assert_eq!(bjorn::interpret("1 > 1"), Ok(String::from("false")));
+    assert_eq!(bjorn::interpret("1 > 2"), Ok(String::from("false")));
+    assert_eq!(bjorn::interpret("1.0 > 2"), Ok(String::from("false")));
+    assert_eq!(bjorn::interpret("1.0 > 1"), Ok(String::from("false")));
+    assert_eq!(bjorn::interpret("2.0 > 1"), Ok(String::from("true")));
+    assert_eq!(bjorn::interpret("1 > 2.0"), Ok(String::from("false")));
+    assert_eq!(bjorn::interpret("1 > 1.0"), Ok(String::from("false")));
+    assert_eq!(bjorn::interpret("2 > 1.0"), Ok(String::from("true")));
+    assert_eq!(bjorn::interpret("1.0 > 2.0"), Ok(String::from("false")));
+    assert_eq!(bjorn::interpret("1.0 > 1.0"), Ok(String::from("false")));
+    assert_eq!(bjorn::interpret("2.0 > 1.0"), Ok(String::from("true")));
 }
 
 #[test]
-#[should_panic]
 fn invalid_comparisons() {
 
     let operands = vec!["==", "!=", "<=", ">=", "<", ">"];
 
     for op in operands {
-        bjorn::interpret(&format!("true {} 1", op));
-        bjorn::interpret(&format!("1 {} true", op));
-        bjorn::interpret(&format!("true {} 1.0", op));
-        bjorn::interpret(&format!("1.0 {} true", op));
-        bjorn::interpret(&format!("false {} 1", op));
-        bjorn::interpret(&format!("1 {} false", op));
-        bjorn::interpret(&format!("false {} 1.0", op));
-        bjorn::interpret(&format!("1.0 {} false", op));
+        assert_type_error(&format!("true {} 1", op));
+        assert_type_error(&format!("1 {} true", op));
+        assert_type_error(&format!("true {} 1.0", op));
+        assert_type_error(&format!("1.0 {} true", op));
+        assert_type_error(&format!("false {} 1", op));
+        assert_type_error(&format!("1 {} false", op));
+        assert_type_error(&format!("false {} 1.0", op));
+        assert_type_error(&format!("1.0 {} false", op));
     }
 
 
 
 }
+
+#[test]
+fn chained_comparisons() {
+    assert_eq!(bjorn::interpret("x = 5\n1 < x < 10"), Ok(String::from("true")));
+    assert_eq!(bjorn::interpret("x = 15\n1 < x < 10"), Ok(String::from("false")));
+    assert_eq!(bjorn::interpret("1 < 2 <= 2 == 2.0 != 3 > 0"), Ok(String::from("true")));
+    assert_eq!(bjorn::interpret("3 > 2 > 1"), Ok(String::from("true")));
+    assert_eq!(bjorn::interpret("1 < 2 + 3 < 4 * 2"), Ok(String::from("true")));
+    // Later comparisons are not evaluated once one fails
+    assert_eq!(bjorn::interpret("2 < 1 < true"), Ok(String::from("false")));
+    assert_type_error("1 < 2 < true");
+}
+
+#[test]
+fn chained_comparisons_evaluate_operands_once() {
+    assert_eq!(
+        bjorn::interpret("calls = 0\ndef middle():\n    global calls\n    calls += 1\n    return 5\n1 < middle() < 10\ncalls"),
+        Ok(String::from("1"))
+    );
+}
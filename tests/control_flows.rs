@@ -1,11 +1,98 @@
 extern crate bjorn;
 
+use bjorn::BjornError;
+
 #[test]
 fn if_statement() {
-    assert_eq!(bjorn::interpret("if true:\n    a = 1\nelse:\n    a = 2\na"), String::from("1"));
+    assert_eq!(bjorn::interpret("if true:\n    a = 1\nelse:\n    a = 2\na"), Ok(String::from("1")));
 }
 
 #[test]
 fn while_statement() {
-    assert_eq!(bjorn::interpret("i = 0\nwhile i < 2:\n    i = i + 1\ni"), String::from("2"))
+    assert_eq!(bjorn::interpret("i = 0\nwhile i < 2:\n    i = i + 1\ni"), Ok(String::from("2")))
+}
+
+#[test]
+fn indentation_widths() {
+    assert_eq!(
+        bjorn::interpret("i = 0\nwhile i < 2:\n  if i == 0:\n  \ti = i + 2\n  else:\n      i = i + 1\ni"),
+        Ok(String::from("2"))
+    );
+}
+
+#[test]
+fn for_statement() {
+    assert_eq!(bjorn::interpret("s = 0\nfor i in range(5):\n    s = s + i\ns"), Ok(String::from("10")));
+    assert_eq!(bjorn::interpret("s = 0\nfor i in range(1, 10, 3):\n    s = s + i\ns"), Ok(String::from("12")));
+    assert_eq!(bjorn::interpret("s = 0\nfor i in range(3, 0, -1):\n    s = s * 10 + i\ns"), Ok(String::from("321")));
+    assert_eq!(bjorn::interpret("n = 0\nfor c in \"héllo\":\n    n = n + 1\nn"), Ok(String::from("5")));
+    assert_eq!(bjorn::interpret("i = 7\nfor i in range(0):\n    i = 1\ni"), Ok(String::from("7")));
+}
+
+#[test]
+fn for_statement_return() {
+    assert_eq!(
+        bjorn::interpret("def f(n):\n    for i in range(n):\n        if i == 3:\n            return i\n    return 0\nf(10)"),
+        Ok(String::from("3"))
+    );
+}
+
+#[test]
+fn break_statement() {
+    assert_eq!(bjorn::interpret("i = 0\nwhile true:\n    i = i + 1\n    if i == 3:\n        break\ni"), Ok(String::from("3")));
+    assert_eq!(
+        bjorn::interpret("s = 0\nfor i in range(3):\n    for j in range(10):\n        if j > i:\n            break\n        s = s + 1\ns"),
+        Ok(String::from("6"))
+    );
+}
+
+#[test]
+fn continue_statement() {
+    assert_eq!(
+        bjorn::interpret("s = 0\nfor i in range(6):\n    if i == 2 or i == 4:\n        continue\n    s = s + i\ns"),
+        Ok(String::from("9"))
+    );
+    assert_eq!(
+        bjorn::interpret("i = 0\nn = 0\nwhile i < 5:\n    i = i + 1\n    if i < 3:\n        continue\n    n = n + 1\nn"),
+        Ok(String::from("3"))
+    );
+}
+
+#[test]
+fn nested_control_flows() {
+    assert_eq!(
+        bjorn::interpret("def f(n):\n    i = 0\n    while true:\n        if i == n:\n            return i * 10\n        i = i + 1\n    return 0\nf(4)"),
+        Ok(String::from("40"))
+    );
+    assert_eq!(
+        bjorn::interpret("def f():\n    while true:\n        break\n    return 1\nf()"),
+        Ok(String::from("1"))
+    );
+    assert_eq!(
+        bjorn::interpret("a = 0\nif false:\n    a = 1\nelse if true:\n    a = 2\nelse:\n    a = 3\na"),
+        Ok(String::from("2"))
+    );
+}
+
+#[test]
+fn loop_control_outside_loop() {
+    match bjorn::interpret("def f():\n    break\n") {
+        Err(BjornError::SyntaxError(..)) => {},
+        other => panic!("expected a syntax error, got {:?}", other),
+    }
+}
+
+#[test]
+fn conditional_expression() {
+    assert_eq!(bjorn::interpret("1 if true else 2"), Ok(String::from("1")));
+    assert_eq!(bjorn::interpret("1 if false else 2"), Ok(String::from("2")));
+    assert_eq!(bjorn::interpret("x = 5\n\"small\" if x < 3 else \"medium\" if x < 10 else \"large\""), Ok(String::from("medium")));
+    assert_eq!(bjorn::interpret("(1 if false else 2) + 1"), Ok(String::from("3")));
+    assert_eq!(
+        bjorn::interpret("def fibonacci(n):\n    return 1 if n <= 1 else fibonacci(n - 2) + fibonacci(n - 1)\nfibonacci(10)"),
+        Ok(String::from("89"))
+    );
+    // Only the selected branch is evaluated
+    assert_eq!(bjorn::interpret("x = 0\n1 if x == 0 else 1 / x"), Ok(String::from("1")));
+    assert_eq!(bjorn::interpret("x = 0\nundefined if x != 0 else 2"), Ok(String::from("2")));
 }
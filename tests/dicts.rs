@@ -0,0 +1,102 @@
+extern crate bjorn;
+
+mod common;
+
+use bjorn::BjornError;
+use common::{assert_type_error, assert_runtime_error};
+
+#[test]
+fn dict_literals() {
+    assert_eq!(bjorn::interpret("{}"), Ok(String::from("{}")));
+    assert_eq!(bjorn::interpret("{'a': 1, 2: [true], 1.5: {}}"), Ok(String::from("{\"a\": 1, 2: [true], 1.5: {}}")));
+    assert_eq!(bjorn::interpret("{\n    'a': 1,\n    'b': 2,\n}"), Ok(String::from("{\"a\": 1, \"b\": 2}")));
+    assert_eq!(bjorn::interpret("{'a': 1, 'b': 2, 'a': 3}"), Ok(String::from("{\"a\": 3, \"b\": 2}")));
+}
+
+#[test]
+fn dict_indexing() {
+    assert_eq!(bjorn::interpret("d = {'a': 1, 'b': 2}\nd['b']"), Ok(String::from("2")));
+    assert_eq!(bjorn::interpret("d = {1: 'one'}\nd[1.0]"), Ok(String::from("one")));
+    assert_eq!(bjorn::interpret("d = {0.0: 'zero'}\nd[-0.0]"), Ok(String::from("zero")));
+    assert_runtime_error("{'a': 1}['b']");
+    assert_runtime_error("{1: 1}[true]");
+    assert_runtime_error("{1: 1}['1']");
+}
+
+#[test]
+fn dict_mutation() {
+    assert_eq!(bjorn::interpret("d = {}\nd['a'] = 1\nd['b'] = 2\nd"), Ok(String::from("{\"a\": 1, \"b\": 2}")));
+    assert_eq!(bjorn::interpret("d = {'a': 1, 'b': 2}\nd['a'] = 3\nd"), Ok(String::from("{\"a\": 3, \"b\": 2}")));
+    assert_eq!(bjorn::interpret("d = {1: 0}\nd[1.0] += 5\nd"), Ok(String::from("{1: 5}")));
+    assert_eq!(bjorn::interpret("d = {'xs': [1]}\nd['xs'][0] = 2\nd"), Ok(String::from("{\"xs\": [2]}")));
+    assert_runtime_error("d = {}\nd['a'] += 1");
+}
+
+#[test]
+fn dict_aliasing() {
+    assert_eq!(bjorn::interpret("a = {}\nb = a\nb['x'] = 1\na"), Ok(String::from("{\"x\": 1}")));
+    assert_eq!(bjorn::interpret("def f(d):\n    d[0] = 0\na = {}\nf(a)\na"), Ok(String::from("{0: 0}")));
+}
+
+#[test]
+fn dict_equality() {
+    assert_eq!(bjorn::interpret("{'a': 1, 'b': 2} == {'b': 2, 'a': 1}"), Ok(String::from("true")));
+    assert_eq!(bjorn::interpret("{1: [1]} == {1.0: [1.0]}"), Ok(String::from("true")));
+    assert_eq!(bjorn::interpret("{'a': 1} == {'a': 2}"), Ok(String::from("false")));
+    assert_eq!(bjorn::interpret("{'a': 1} != {'a': 1, 'b': 2}"), Ok(String::from("true")));
+}
+
+#[test]
+fn dict_membership() {
+    assert_eq!(bjorn::interpret("'a' in {'a': 1}"), Ok(String::from("true")));
+    assert_eq!(bjorn::interpret("1 in {'a': 1}"), Ok(String::from("false")));
+    assert_eq!(bjorn::interpret("1.0 in {1: 'a'}"), Ok(String::from("true")));
+    assert_type_error("[1] in {}");
+}
+
+#[test]
+fn dict_methods() {
+    let d = "d = {'b': 1, 'a': 2}\nd['c'] = 3\n";
+    assert_eq!(bjorn::interpret(&format!("{}d.keys()", d)), Ok(String::from("[\"b\", \"a\", \"c\"]")));
+    assert_eq!(bjorn::interpret(&format!("{}d.values()", d)), Ok(String::from("[1, 2, 3]")));
+    assert_eq!(bjorn::interpret(&format!("{}d.items()", d)), Ok(String::from("[(\"b\", 1), (\"a\", 2), (\"c\", 3)]")));
+    assert_eq!(bjorn::interpret("keys = {1: 2}.keys\nkeys()"), Ok(String::from("[1]")));
+    assert_eq!(bjorn::interpret("{}.keys"), Ok(String::from("<method dict.keys>")));
+    assert_type_error("{}.get()");
+    assert_type_error("[].keys()");
+    match bjorn::interpret("{}.keys(1)") {
+        Err(BjornError::ArityError(..)) => {},
+        other => panic!("expected an arity error, got {:?}", other),
+    }
+}
+
+#[test]
+fn dict_iteration() {
+    assert_eq!(
+        bjorn::interpret("s = ''\nfor k in {'x': 1, 'y': 2, 'z': 3}:\n    s += k\ns"),
+        Ok(String::from("xyz"))
+    );
+    assert_eq!(
+        bjorn::interpret("d = {'a': 1}\nfor k in d:\n    d[k + 'b'] = 2\nd"),
+        Ok(String::from("{\"a\": 1, \"ab\": 2}"))
+    );
+}
+
+#[test]
+fn unhashable_keys() {
+    assert_type_error("{[1]: 2}");
+    assert_type_error("{}[[1]]");
+    assert_type_error("d = {}\nd[{}] = 1");
+    assert_type_error("def f():\n    return 1\n{f: 1}");
+    // `NaN` is not equal to itself
+    let nan = "nan = 1e308 * 10 - 1e308 * 10\n";
+    assert_type_error(&format!("{}{{nan: 1}}", nan));
+    assert_type_error(&format!("{}d = {{}}\nd[nan] = 1", nan));
+    assert_type_error(&format!("{}nan in {{1: 1}}", nan));
+}
+
+#[test]
+fn builtin_len() {
+    assert_eq!(bjorn::interpret("len({})"), Ok(String::from("0")));
+    assert_eq!(bjorn::interpret("len({'a': 1, 'b': 2, 'a': 3})"), Ok(String::from("2")));
+}
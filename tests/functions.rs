@@ -1,19 +1,193 @@
 extern crate bjorn;
 
+use bjorn::BjornError;
+
 #[test]
 fn function_calls() {
-    assert_eq!(bjorn::interpret("def test():\n    return 1\ntest()"), String::from("1"));
-    assert_eq!(bjorn::interpret("def test(x):\n    return x\ntest(1)"), String::from("1"));
+    assert_eq!(bjorn::interpret("def test():\n    return 1\ntest()"), Ok(String::from("1")));
+    assert_eq!(bjorn::interpret("def test(x):\n    return x\ntest(1)"), Ok(String::from("1")));
     assert_eq!(
         bjorn::interpret("def test(x, y):\n    return x + y\ntest(1, 2)"),
-        String::from("3")
+        Ok(String::from("3"))
     );
     assert_eq!(
         bjorn::interpret("def test():\n    if true:\n        return 1\n    else:\n        return 2\ntest()"),
-        String::from("1")
+        Ok(String::from("1"))
     );
     assert_eq!(
         bjorn::interpret("def test(i):\n    while i < 2:\n        i = i + 1\n    return i\ntest(0)"),
-        String::from("2")
+        Ok(String::from("2"))
+    );
+}
+
+#[test]
+fn function_calls_with_wrong_arity() {
+    for input in &["def test(x):\n    return x\ntest()", "def test():\n    return 1\ntest(1)"] {
+        match bjorn::interpret(input) {
+            Err(BjornError::ArityError(..)) => {},
+            other => panic!("expected an arity error for `{}`, got {:?}", input, other),
+        }
+    }
+}
+
+#[test]
+fn undefined_function() {
+    match bjorn::interpret("test()") {
+        Err(BjornError::NameError(..)) => {},
+        other => panic!("expected a name error, got {:?}", other),
+    }
+}
+
+#[test]
+fn traceback() {
+    let input = "def f(n):\n    if n <= 0:\n        return n + true\n    return f(n - 1)\nf(1)";
+    let error = bjorn::interpret(input).unwrap_err();
+    let frames: Vec<(String, usize)> = error.traceback().iter()
+        .map(|frame| (frame.to_string(), frame.span.line))
+        .collect();
+    assert_eq!(frames, vec![(String::from("f(1)"), 5), (String::from("f(0)"), 4)]);
+    assert_eq!(error.span().map(|span| span.line), Some(3));
+
+    // Errors raised outside of any function have no traceback.
+    assert!(bjorn::interpret("1 + true").unwrap_err().traceback().is_empty());
+}
+
+#[test]
+fn undefined_function_suggestion() {
+    let error = bjorn::interpret("def fibonacci(n):\n    return n\nfibonaci(1)").unwrap_err();
+    assert_eq!(error.help(), Some("did you mean `fibonacci`?"));
+}
+
+#[test]
+fn multiline_function_calls() {
+    assert_eq!(
+        bjorn::interpret("def add(a,\n        b):\n    return a + \\\n        b\nadd(1,\n    2)"),
+        Ok(String::from("3"))
+    );
+    assert_eq!(bjorn::interpret("(1 +\n  (2 *\n3))"), Ok(String::from("7")));
+}
+
+#[test]
+fn lexical_scoping() {
+    // Functions read globals and call the other top-level functions
+    assert_eq!(
+        bjorn::interpret("LIMIT = 10\ndef square(x):\n    return x * x\ndef bounded(x):\n    return square(x) < LIMIT\nbounded(3)"),
+        Ok(String::from("true"))
+    );
+    assert_eq!(
+        bjorn::interpret("def is_even(n):\n    if n == 0:\n        return true\n    return is_odd(n - 1)\ndef is_odd(n):\n    if n == 0:\n        return false\n    return is_even(n - 1)\nis_even(10)"),
+        Ok(String::from("true"))
+    );
+    // Assignments are local unless declared otherwise
+    assert_eq!(bjorn::interpret("a = 1\ndef f():\n    a = 2\n    return a\nf() + a"), Ok(String::from("3")));
+    // Scopes of the callers are not visible
+    match bjorn::interpret("def f():\n    return local\ndef g():\n    local = 1\n    return f()\ng()") {
+        Err(BjornError::NameError(..)) => {},
+        other => panic!("expected a name error, got {:?}", other),
+    }
+    // Builtins can be shadowed without being lost
+    assert_eq!(bjorn::interpret("def f(print):\n    return print\nf(1)\nprint(2)"), Ok(String::from("")));
+}
+
+#[test]
+fn global_declarations() {
+    assert_eq!(
+        bjorn::interpret("count = 0\ndef increment():\n    global count\n    count += 1\nincrement()\nincrement()\ncount"),
+        Ok(String::from("2"))
+    );
+    assert_eq!(bjorn::interpret("def f():\n    global a\n    a = 1\nf()\na"), Ok(String::from("1")));
+    assert_eq!(bjorn::interpret("global a\na = 1\na"), Ok(String::from("1")));
+    // Reads of a global name skip the enclosing scopes
+    assert_eq!(
+        bjorn::interpret("x = 0\ndef f():\n    x = 1\n    def g():\n        global x\n        x = 5\n        return x\n    return g()\nf()"),
+        Ok(String::from("5"))
+    );
+    assert_eq!(
+        bjorn::interpret("x = 0\ndef f():\n    x = 1\n    def g():\n        global x\n        return x\n    return g()\nf()"),
+        Ok(String::from("0"))
+    );
+    // A name bound locally cannot be declared global afterwards
+    match bjorn::interpret("x = 0\ndef f():\n    x = 1\n    global x\n    x = 2\n    return x\nf()") {
+        Err(BjornError::SyntaxError(..)) => {},
+        other => panic!("expected a syntax error, got {:?}", other),
+    }
+    match bjorn::interpret("def f(x):\n    global x\nf(1)") {
+        Err(BjornError::SyntaxError(..)) => {},
+        other => panic!("expected a syntax error, got {:?}", other),
+    }
+}
+
+#[test]
+fn nonlocal_declarations() {
+    assert_eq!(
+        bjorn::interpret("def counter():\n    count = 0\n    def increment():\n        nonlocal count\n        count += 1\n        return count\n    return increment\nc = counter()\nc()\nc()\nc()"),
+        Ok(String::from("3"))
+    );
+    match bjorn::interpret("a = 1\ndef f():\n    nonlocal a\n    a = 2\nf()") {
+        Err(BjornError::SyntaxError(..)) => {},
+        other => panic!("expected a syntax error, got {:?}", other),
+    }
+    // Reads of a nonlocal name use the innermost enclosing binding
+    assert_eq!(
+        bjorn::interpret("def f():\n    x = 1\n    def g():\n        x = 2\n        def h():\n            nonlocal x\n            return x\n        return h()\n    return g()\nf()"),
+        Ok(String::from("2"))
+    );
+    match bjorn::interpret("def f():\n    x = 1\n    def g():\n        x = 2\n        nonlocal x\n    g()\nf()") {
+        Err(BjornError::SyntaxError(..)) => {},
+        other => panic!("expected a syntax error, got {:?}", other),
+    }
+}
+
+#[test]
+fn closures() {
+    assert_eq!(
+        bjorn::interpret("def make_adder(n):\n    def add(x):\n        return x + n\n    return add\nmake_adder(1)(2)"),
+        Ok(String::from("3"))
+    );
+    // Each call captures its own scope
+    assert_eq!(
+        bjorn::interpret("def make_adder(n):\n    return lambda x: x + n\nadd1 = make_adder(1)\nadd10 = make_adder(10)\nadd1(0) + add10(0)"),
+        Ok(String::from("11"))
+    );
+    // Nested functions can be recursive
+    assert_eq!(
+        bjorn::interpret("def f(n):\n    def fact(n):\n        if n == 0:\n            return 1\n        return n * fact(n - 1)\n    return fact(n)\nf(5)"),
+        Ok(String::from("120"))
+    );
+}
+
+#[test]
+fn first_class_functions() {
+    assert_eq!(
+        bjorn::interpret("def twice(f, x):\n    return f(f(x))\ndef double(x):\n    return x * 2\ntwice(double, 3)"),
+        Ok(String::from("12"))
+    );
+    assert_eq!(bjorn::interpret("twice = lambda f, x: f(f(x))\ntwice(lambda x: x + 1, 0)"), Ok(String::from("2")));
+    assert_eq!(bjorn::interpret("(lambda: 42)()"), Ok(String::from("42")));
+    assert_eq!(bjorn::interpret("p = print\np(1)"), Ok(String::from("")));
+    assert_eq!(bjorn::interpret("def f():\n    return 1\nf"), Ok(String::from("<function f>")));
+    match bjorn::interpret("f = 1\nf()") {
+        Err(BjornError::TypeError(..)) => {},
+        other => panic!("expected a type error, got {:?}", other),
+    }
+    match bjorn::interpret("(lambda x: x)()") {
+        Err(BjornError::ArityError(..)) => {},
+        other => panic!("expected an arity error, got {:?}", other),
+    }
+}
+
+#[test]
+fn recursion_limit() {
+    assert_eq!(
+        bjorn::interpret("def depth(n):\n    return 0 if n == 0 else depth(n - 1) + 1\ndepth(900)"),
+        Ok(String::from("900"))
     );
+    match bjorn::interpret("def f(n):\n    return f(n + 1)\nf(0)") {
+        Err(error @ BjornError::RuntimeError(..)) => {
+            assert_eq!(error.message(), "maximum recursion depth exceeded");
+            assert_eq!(error.traceback().len(), 1000);
+            assert_eq!(error.traceback()[999].arguments, vec![String::from("999")]);
+        },
+        other => panic!("expected a runtime error, got {:?}", other),
+    }
 }
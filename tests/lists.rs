@@ -0,0 +1,148 @@
+extern crate bjorn;
+
+mod common;
+
+use bjorn::BjornError;
+use common::{assert_type_error, assert_runtime_error};
+
+#[test]
+fn list_literals() {
+    assert_eq!(bjorn::interpret("[]"), Ok(String::from("[]")));
+    assert_eq!(bjorn::interpret("[1, 2.5, 'a', [true]]"), Ok(String::from("[1, 2.5, \"a\", [true]]")));
+    assert_eq!(bjorn::interpret("[\n    1,\n    2,\n]"), Ok(String::from("[1, 2]")));
+}
+
+#[test]
+fn list_indexing() {
+    assert_eq!(bjorn::interpret("xs = [1, 2, 3]\nxs[0]"), Ok(String::from("1")));
+    assert_eq!(bjorn::interpret("xs = [1, 2, 3]\nxs[-1]"), Ok(String::from("3")));
+    assert_eq!(bjorn::interpret("[[1, 2], [3, 4]][1][0]"), Ok(String::from("3")));
+    assert_eq!(bjorn::interpret("'bjørn'[2]"), Ok(String::from("ø")));
+    assert_runtime_error("[1, 2][2]");
+    assert_runtime_error("[1, 2][-3]");
+    assert_type_error("[1, 2]['a']");
+    assert_type_error("1[0]");
+}
+
+#[test]
+fn list_slicing() {
+    let xs = "xs = [0, 1, 2, 3, 4, 5]\n";
+    assert_eq!(bjorn::interpret(&format!("{}xs[1:3]", xs)), Ok(String::from("[1, 2]")));
+    assert_eq!(bjorn::interpret(&format!("{}xs[:2]", xs)), Ok(String::from("[0, 1]")));
+    assert_eq!(bjorn::interpret(&format!("{}xs[4:]", xs)), Ok(String::from("[4, 5]")));
+    assert_eq!(bjorn::interpret(&format!("{}xs[::2]", xs)), Ok(String::from("[0, 2, 4]")));
+    assert_eq!(bjorn::interpret(&format!("{}xs[::-1]", xs)), Ok(String::from("[5, 4, 3, 2, 1, 0]")));
+    assert_eq!(bjorn::interpret(&format!("{}xs[-2:]", xs)), Ok(String::from("[4, 5]")));
+    assert_eq!(bjorn::interpret(&format!("{}xs[4:1:-2]", xs)), Ok(String::from("[4, 2]")));
+    assert_eq!(bjorn::interpret(&format!("{}xs[-100:100]", xs)), Ok(String::from("[0, 1, 2, 3, 4, 5]")));
+    assert_eq!(bjorn::interpret(&format!("{}xs[3:1]", xs)), Ok(String::from("[]")));
+    assert_eq!(bjorn::interpret("'bjørn'[1:-1]"), Ok(String::from("jør")));
+    assert_runtime_error("[1][::0]");
+    assert_type_error("[1][1.0:]");
+}
+
+#[test]
+fn list_mutation() {
+    assert_eq!(bjorn::interpret("xs = [1, 2, 3]\nxs[1] = 5\nxs"), Ok(String::from("[1, 5, 3]")));
+    assert_eq!(bjorn::interpret("xs = [1, 2, 3]\nxs[-1] += 5\nxs"), Ok(String::from("[1, 2, 8]")));
+    assert_eq!(bjorn::interpret("xs = [[1], [2]]\nxs[1][0] *= 3\nxs"), Ok(String::from("[[1], [6]]")));
+    assert_runtime_error("xs = [1]\nxs[1] = 2");
+    assert_type_error("s = 'ab'\ns[0] = 'c'");
+    match bjorn::interpret("xs = [1]\nxs[0:1] = [2]") {
+        Err(BjornError::SyntaxError(..)) => {},
+        other => panic!("expected a syntax error, got {:?}", other),
+    }
+}
+
+#[test]
+fn indexed_target_evaluated_once() {
+    assert_eq!(
+        bjorn::interpret("calls = 0\nxs = [10, 20]\ndef i():\n    global calls\n    calls += 1\n    return 1\nxs[i()] += 1\n[xs, calls]"),
+        Ok(String::from("[[10, 21], 1]"))
+    );
+}
+
+#[test]
+fn list_aliasing() {
+    assert_eq!(bjorn::interpret("a = [1, 2]\nb = a\nb[0] = 9\na"), Ok(String::from("[9, 2]")));
+    assert_eq!(
+        bjorn::interpret("def clear(xs):\n    xs[0] = 0\na = [1]\nclear(a)\na"),
+        Ok(String::from("[0]"))
+    );
+    // Slices and concatenations are copies
+    assert_eq!(bjorn::interpret("a = [1, 2]\nb = a[:]\nb[0] = 9\na"), Ok(String::from("[1, 2]")));
+    assert_eq!(bjorn::interpret("a = [1]\nb = a + []\nb[0] = 9\na"), Ok(String::from("[1]")));
+}
+
+#[test]
+fn list_operations() {
+    assert_eq!(bjorn::interpret("[1, 2] + [3]"), Ok(String::from("[1, 2, 3]")));
+    assert_eq!(bjorn::interpret("[1, [2]] == [1, [2]]"), Ok(String::from("true")));
+    assert_eq!(bjorn::interpret("[1, 2] == [1]"), Ok(String::from("false")));
+    assert_eq!(bjorn::interpret("[1, 2] < [1, 3]"), Ok(String::from("true")));
+    assert_eq!(bjorn::interpret("[1, 2] < [1]"), Ok(String::from("false")));
+    assert_type_error("[1] + 1");
+}
+
+#[test]
+fn membership() {
+    assert_eq!(bjorn::interpret("2 in [1, 2, 3]"), Ok(String::from("true")));
+    assert_eq!(bjorn::interpret("2.0 in [1, 2]"), Ok(String::from("true")));
+    assert_eq!(bjorn::interpret("[2] in [[1], [2]]"), Ok(String::from("true")));
+    assert_eq!(bjorn::interpret("'jø' in 'bjørn'"), Ok(String::from("true")));
+    assert_eq!(bjorn::interpret("4 in range(0, 10, 2)"), Ok(String::from("true")));
+    assert_eq!(bjorn::interpret("5 in range(0, 10, 2)"), Ok(String::from("false")));
+    assert_eq!(bjorn::interpret("4.0 in range(0, 10, 2)"), Ok(String::from("true")));
+    assert_eq!(bjorn::interpret("4.5 in range(0, 10, 2)"), Ok(String::from("false")));
+    assert_eq!(bjorn::interpret("not 1 in [1]"), Ok(String::from("false")));
+    assert_type_error("1 in 'a'");
+    assert_type_error("1 in 1");
+    // Elements of other types are not equal to `item`, whatever the container
+    assert_eq!(bjorn::interpret("1 in [1, 'a']"), Ok(String::from("true")));
+    assert_eq!(bjorn::interpret("2 in [1, 'a']"), Ok(String::from("false")));
+    assert_eq!(bjorn::interpret("'a' in [1, 2]"), Ok(String::from("false")));
+    assert_eq!(bjorn::interpret("'a' in (1, 2)"), Ok(String::from("false")));
+    assert_eq!(bjorn::interpret("'a' in range(0, 2)"), Ok(String::from("false")));
+    assert_eq!(bjorn::interpret("[1, 'a'] in [[1, 2]]"), Ok(String::from("false")));
+    assert_eq!(bjorn::interpret("1 in [true]"), bjorn::interpret("1 in {true: 0}"));
+    assert_eq!(bjorn::interpret("1 in [true]"), Ok(String::from("false")));
+    assert_type_error("[1, 'a'] == [1, 2]");
+}
+
+#[test]
+fn list_iteration() {
+    assert_eq!(bjorn::interpret("s = 0\nfor x in [1, 2, 3]:\n    s += x\ns"), Ok(String::from("6")));
+}
+
+#[test]
+fn builtin_len() {
+    assert_eq!(bjorn::interpret("len([1, 2, 3])"), Ok(String::from("3")));
+    assert_eq!(bjorn::interpret("len('bjørn')"), Ok(String::from("5")));
+    assert_eq!(bjorn::interpret("len(range(0, 10, 3))"), Ok(String::from("4")));
+    assert_eq!(bjorn::interpret("len(range(10, 0, -3))"), Ok(String::from("4")));
+    assert_eq!(bjorn::interpret("len(range(0, 10, -1))"), Ok(String::from("0")));
+    assert_eq!(bjorn::interpret("len(range(200000000))"), Ok(String::from("200000000")));
+    assert_eq!(
+        bjorn::interpret("n = 0\nfor i in range(-7, 8, 4):\n    n += 1\nn == len(range(-7, 8, 4))"),
+        Ok(String::from("true"))
+    );
+    assert_runtime_error("len(range(-2147483647 - 1, 2147483647))");
+    assert_type_error("len(1)");
+}
+
+#[test]
+fn self_referencing_lists() {
+    let xs = "xs = [1]\nxs[0] = xs\n";
+    assert_eq!(bjorn::interpret(&format!("{}xs", xs)), Ok(String::from("[[...]]")));
+    assert_eq!(bjorn::interpret(&format!("{}print(xs)\n[xs, xs]", xs)), Ok(String::from("[[[...]], [[...]]]")));
+    assert_eq!(bjorn::interpret("d = {}\nd['d'] = d\nd"), Ok(String::from("{\"d\": {...}}")));
+    assert_eq!(bjorn::interpret(&format!("{}ys = [1]\nys[0] = ys\nxs == ys", xs)), Ok(String::from("true")));
+    assert_eq!(bjorn::interpret(&format!("{}ys = [[1]]\nys[0][0] = ys\nxs == ys", xs)), Ok(String::from("true")));
+    assert_eq!(bjorn::interpret(&format!("{}ys = [xs, 1]\nxs == ys", xs)), Ok(String::from("false")));
+    match bjorn::interpret(&format!("def f(x):\n    return 1 // 0\n{}f(xs)", xs)) {
+        Err(error @ BjornError::RuntimeError(..)) => {
+            assert_eq!(error.traceback()[0].arguments, vec![String::from("[[...]]")]);
+        },
+        other => panic!("expected a runtime error, got {:?}", other),
+    }
+}
@@ -1,128 +1,216 @@
 extern crate bjorn;
 
+mod common;
+
+use bjorn::BjornError;
+use common::assert_type_error;
+
 #[test]
 fn empty_statement() {
-    assert_eq!(bjorn::interpret(""), String::from(""));
+    assert_eq!(bjorn::interpret(""), Ok(String::from("")));
 }
 
 #[test]
 fn operations_with_integers() {
-    assert_eq!(bjorn::interpret("2 + 2"), String::from("4"));
-    assert_eq!(bjorn::interpret("2 - 2"), String::from("0"));
-    assert_eq!(bjorn::interpret("2 + 2 - 3"), String::from("1"));
-    assert_eq!(bjorn::interpret("2 * 2"), String::from("4"));
-    assert_eq!(bjorn::interpret("2 + 2 * 3"), String::from("8"));
-    assert_eq!(bjorn::interpret("2 / 2"), String::from("1"));
-    assert_eq!(bjorn::interpret("2 / 2 * 2"), String::from("2"));
+    assert_eq!(bjorn::interpret("2 + 2"), Ok(String::from("4")));
+    assert_eq!(bjorn::interpret("2 - 2"), Ok(String::from("0")));
+    assert_eq!(bjorn::interpret("2 + 2 - 3"), Ok(String::from("1")));
+    assert_eq!(bjorn::interpret("2 * 2"), Ok(String::from("4")));
+    assert_eq!(bjorn::interpret("2 + 2 * 3"), Ok(String::from("8")));
+    assert_eq!(bjorn::interpret("2 / 2"), Ok(String::from("1")));
+    assert_eq!(bjorn::interpret("2 / 2 * 2"), Ok(String::from("2")));
 }
 
 #[test]
 fn operations_with_floats() {
-    assert_eq!(bjorn::interpret("2.0 + 2.0"), String::from("4"));
-    assert_eq!(bjorn::interpret("2.0 - 2.0"), String::from("0"));
-    assert_eq!(bjorn::interpret("2.5 + 2.5 - 3.5"), String::from("1.5"));
-    assert_eq!(bjorn::interpret("2.0 * 2.0"), String::from("4"));
-    assert_eq!(bjorn::interpret("2.5 + 2.5 * 3"), String::from("10"));
-    assert_eq!(bjorn::interpret("2.0 / 2.0"), String::from("1"));
-    assert_eq!(bjorn::interpret("2.0 / 2.0 * 2.5"), String::from("2.5"));
-    assert_eq!(bjorn::interpret("2.0 + 2"), String::from("4"));
-    assert_eq!(bjorn::interpret("2.5 + 2"), String::from("4.5"));
-    assert_eq!(bjorn::interpret("2 + 2.5"), String::from("4.5"));
-    assert_eq!(bjorn::interpret("4.0 - 2"), String::from("2"));
-    assert_eq!(bjorn::interpret("4 - 2.0"), String::from("2"));
-    assert_eq!(bjorn::interpret("4.0 * 2"), String::from("8"));
-    assert_eq!(bjorn::interpret("3 * 1.5"), String::from("4.5"));
-    assert_eq!(bjorn::interpret("4.0 / 2"), String::from("2"));
-    assert_eq!(bjorn::interpret("4 / 2.0"), String::from("2"));
-    assert_eq!(bjorn::interpret("5 / 2.0"), String::from("2.5"));
+    assert_eq!(bjorn::interpret("2.0 + 2.0"), Ok(String::from("4")));
+    assert_eq!(bjorn::interpret("2.0 - 2.0"), Ok(String::from("0")));
+    assert_eq!(bjorn::interpret("2.5 + 2.5 - 3.5"), Ok(String::from("1.5")));
+    assert_eq!(bjorn::interpret("2.0 * 2.0"), Ok(String::from("4")));
+    assert_eq!(bjorn::interpret("2.5 + 2.5 * 3"), Ok(String::from("10")));
+    assert_eq!(bjorn::interpret("2.0 / 2.0"), Ok(String::from("1")));
+    assert_eq!(bjorn::interpret("2.0 / 2.0 * 2.5"), Ok(String::from("2.5")));
+    assert_eq!(bjorn::interpret("2.0 + 2"), Ok(String::from("4")));
+    assert_eq!(bjorn::interpret("2.5 + 2"), Ok(String::from("4.5")));
+    assert_eq!(bjorn::interpret("2 + 2.5"), Ok(String::from("4.5")));
+    assert_eq!(bjorn::interpret("4.0 - 2"), Ok(String::from("2")));
+    assert_eq!(bjorn::interpret("4 - 2.0"), Ok(String::from("2")));
+    assert_eq!(bjorn::interpret("4.0 * 2"), Ok(String::from("8")));
+    assert_eq!(bjorn::interpret("3 * 1.5"), Ok(String::from("4.5")));
+    assert_eq!(bjorn::interpret("4.0 / 2"), Ok(String::from("2")));
+    assert_eq!(bjorn::interpret("4 / 2.0"), Ok(String::from("2")));
+    assert_eq!(bjorn::interpret("5 / 2.0"), Ok(String::from("2.5")));
+}
+
+#[test]
+fn integer_division_and_modulo() {
+    assert_eq!(bjorn::interpret("7 // 2"), Ok(String::from("3")));
+    assert_eq!(bjorn::interpret("-7 // 2"), Ok(String::from("-4")));
+    assert_eq!(bjorn::interpret("7 // -2"), Ok(String::from("-4")));
+    assert_eq!(bjorn::interpret("-7 // -2"), Ok(String::from("3")));
+    assert_eq!(bjorn::interpret("7 % 3"), Ok(String::from("1")));
+    assert_eq!(bjorn::interpret("-7 % 3"), Ok(String::from("2")));
+    assert_eq!(bjorn::interpret("7 % -3"), Ok(String::from("-2")));
+    assert_eq!(bjorn::interpret("-7 % -3"), Ok(String::from("-1")));
+    assert_eq!(bjorn::interpret("7.5 // 2"), Ok(String::from("3")));
+    assert_eq!(bjorn::interpret("-7.5 % 2"), Ok(String::from("0.5")));
+    assert_eq!(bjorn::interpret("2 + 7 % 4 * 2"), Ok(String::from("8")));
+}
+
+#[test]
+fn float_division_and_modulo() {
+    assert_eq!(bjorn::interpret("1 // 0.1"), Ok(String::from("9")));
+    assert_eq!(bjorn::interpret("-1 // 0.1"), Ok(String::from("-10")));
+    assert_eq!(bjorn::interpret("-7.5 // 2"), Ok(String::from("-4")));
+    assert_eq!(bjorn::interpret("-4.0 % 2"), Ok(String::from("0")));
+    // The quotient and the remainder give back the dividend
+    for (a, b) in &[("1", "0.1"), ("-1", "0.1"), ("1", "-0.1"), ("0.3", "0.1"), ("7.5", "-2"), ("1e10", "0.7")] {
+        let program = format!("a = {}\nb = {}\nd = a - ((a // b) * b + a % b)\nd < 1e-9 and d > -1e-9", a, b);
+        assert_eq!(bjorn::interpret(&program), Ok(String::from("true")), "for {} and {}", a, b);
+    }
+}
+
+#[test]
+fn exponentiation() {
+    assert_eq!(bjorn::interpret("2 ** 10"), Ok(String::from("1024")));
+    assert_eq!(bjorn::interpret("2 ** 3 ** 2"), Ok(String::from("512")));
+    assert_eq!(bjorn::interpret("-2 ** 2"), Ok(String::from("-4")));
+    assert_eq!(bjorn::interpret("(-2) ** 2"), Ok(String::from("4")));
+    assert_eq!(bjorn::interpret("2 ** -1"), Ok(String::from("0.5")));
+    assert_eq!(bjorn::interpret("4 ** 0.5"), Ok(String::from("2")));
+    assert_eq!(bjorn::interpret("2 * 3 ** 2"), Ok(String::from("18")));
+}
+
+#[test]
+fn invalid_arithmetic() {
+    for input in &["1 / 0", "1 // 0", "1 % 0", "1.5 // 0.0", "1.5 % 0", "0 ** -1", "(-8) ** 0.5", "2 ** 31", "(-2147483647 - 1) // -1"] {
+        match bjorn::interpret(input) {
+            Err(BjornError::RuntimeError(..)) => {},
+            other => panic!("expected a runtime error for `{}`, got {:?}", input, other),
+        }
+    }
+    assert_type_error("\"a\" % 2");
+    assert_type_error("2 ** true");
+    assert_type_error("true // 1");
 }
 
 #[test]
 fn operations_with_unary_expressions() {
-    assert_eq!(bjorn::interpret("+2 + +2"), String::from("4"));
-    assert_eq!(bjorn::interpret("+2 + -2"), String::from("0"));
-    assert_eq!(bjorn::interpret("-2 + +2"), String::from("0"));
-    assert_eq!(bjorn::interpret("-2 + -2"), String::from("-4"));
-    assert_eq!(bjorn::interpret("++2"), String::from("2"));
-    assert_eq!(bjorn::interpret("-+2"), String::from("-2"));
-    assert_eq!(bjorn::interpret("+-2"), String::from("-2"));
-    assert_eq!(bjorn::interpret("--2"), String::from("2"));
+    assert_eq!(bjorn::interpret("+2 + +2"), Ok(String::from("4")));
+    assert_eq!(bjorn::interpret("+2 + -2"), Ok(String::from("0")));
+    assert_eq!(bjorn::interpret("-2 + +2"), Ok(String::from("0")));
+    assert_eq!(bjorn::interpret("-2 + -2"), Ok(String::from("-4")));
+    assert_eq!(bjorn::interpret("++2"), Ok(String::from("2")));
+    assert_eq!(bjorn::interpret("-+2"), Ok(String::from("-2")));
+    assert_eq!(bjorn::interpret("+-2"), Ok(String::from("-2")));
+    assert_eq!(bjorn::interpret("--2"), Ok(String::from("2")));
 }
 
 #[test]
 fn operations_logical_with_boolean() {
     // OR
-    assert_eq!(bjorn::interpret("true or true"), String::from("true"));
-    assert_eq!(bjorn::interpret("true or false"), String::from("true"));
-    assert_eq!(bjorn::interpret("false or true"), String::from("true"));
-    assert_eq!(bjorn::interpret("false or false"), String::from("false"));
+    assert_eq!(bjorn::interpret("true or true"), Ok(String::from("true")));
+    assert_eq!(bjorn::interpret("true or false"), Ok(String::from("true")));
+    assert_eq!(bjorn::interpret("false or true"), Ok(String::from("true")));
+    assert_eq!(bjorn::interpret("false or false"), Ok(String::from("false")));
 
     // AND
-    assert_eq!(bjorn::interpret("true and true"), String::from("true"));
-    assert_eq!(bjorn::interpret("true and false"), String::from("false"));
-    assert_eq!(bjorn::interpret("false and true"), String::from("false"));
-    assert_eq!(bjorn::interpret("false and false"), String::from("false"));
+    assert_eq!(bjorn::interpret("true and true"), Ok(String::from("true")));
+    assert_eq!(bjorn::interpret("true and false"), Ok(String::from("false")));
+    assert_eq!(bjorn::interpret("false and true"), Ok(String::from("false")));
+    assert_eq!(bjorn::interpret("false and false"), Ok(String::from("false")));
 
     // NOT
-    assert_eq!(bjorn::interpret("not true"), String::from("false"));
-    assert_eq!(bjorn::interpret("not false"), String::from("true"));
+    assert_eq!(bjorn::interpret("not true"), Ok(String::from("false")));
+    assert_eq!(bjorn::interpret("not false"), Ok(String::from("true")));
+}
+
+#[test]
+fn short_circuit_evaluation() {
+    // The right operand would fail if evaluated
+    assert_eq!(bjorn::interpret("x = 0\nx != 0 and 10 / x > 1"), Ok(String::from("false")));
+    assert_eq!(bjorn::interpret("x = 0\nx == 0 or 10 / x > 1"), Ok(String::from("true")));
+    assert_eq!(bjorn::interpret("true or 1"), Ok(String::from("true")));
+    assert_eq!(bjorn::interpret("false and 1"), Ok(String::from("false")));
+
+    // Side effects of the right operand only happen when it is evaluated
+    let program = "calls = 0\ndef check(result):\n    global calls\n    calls += 1\n    return result\n";
+    assert_eq!(bjorn::interpret(&format!("{}false and check(true)\ncalls", program)), Ok(String::from("0")));
+    assert_eq!(bjorn::interpret(&format!("{}true and check(true)\ncalls", program)), Ok(String::from("1")));
+    assert_eq!(bjorn::interpret(&format!("{}true or check(true)\ncalls", program)), Ok(String::from("0")));
+    assert_eq!(bjorn::interpret(&format!("{}false or check(false) or check(true)\ncalls", program)), Ok(String::from("2")));
 }
 
 #[test]
-#[should_panic]
 fn invalid_operations_with_booleans() {
 
     let operands = vec!("+", "-", "*", "/");
 
     for op in operands {
         // with integers
-        bjorn::interpret(&format!("1 {} true", op));
-        bjorn::interpret(&format!("1 {} false", op));
-        bjorn::interpret(&format!("true {} 1", op));
-        bjorn::interpret(&format!("false {} 1", op));
+        assert_type_error(&format!("1 {} true", op));
+        assert_type_error(&format!("1 {} false", op));
+        assert_type_error(&format!("true {} 1", op));
+        assert_type_error(&format!("false {} 1", op));
 
         // with Floats
-        bjorn::interpret(&format!("1.0 {} true", op));
-        bjorn::interpret(&format!("1.0 {} false", op));
-        bjorn::interpret(&format!("true {} 1.0", op));
-        bjorn::interpret(&format!("false {} 1.0", op));
+        assert_type_error(&format!("1.0 {} true", op));
+        assert_type_error(&format!("1.0 {} false", op));
+        assert_type_error(&format!("true {} 1.0", op));
+        assert_type_error(&format!("false {} 1.0", op));
 
         // with boolean
-        bjorn::interpret(&format!("true {} true", op));
-        bjorn::interpret(&format!("true {} false", op));
-        bjorn::interpret(&format!("false {} true", op));
-        bjorn::interpret(&format!("false {} false", op));
+        assert_type_error(&format!("true {} true", op));
+        assert_type_error(&format!("true {} false", op));
+        assert_type_error(&format!("false {} true", op));
+        assert_type_error(&format!("false {} false", op));
     }
 }
 
 #[test]
-#[should_panic]
 fn invalid_logical_operations_with_numbers() {
 
     // OR and AND
     let operands = vec!("or", "and");
     for op in operands {
         // with integers and floats
-        bjorn::interpret(&format!("1 {} 1", op));
-        bjorn::interpret(&format!("1 {} 1.0", op));
-        bjorn::interpret(&format!("1.0 {} 1", op));
-        bjorn::interpret(&format!("1.0 {} 1.0", op));
+        assert_type_error(&format!("1 {} 1", op));
+        assert_type_error(&format!("1 {} 1.0", op));
+        assert_type_error(&format!("1.0 {} 1", op));
+        assert_type_error(&format!("1.0 {} 1.0", op));
 
         // with integers and Booleans
-        bjorn::interpret(&format!("1 {} true", op));
-        bjorn::interpret(&format!("1 {} false", op));
-        bjorn::interpret(&format!("true {} 1", op));
-        bjorn::interpret(&format!("false {} 1", op));
+        assert_type_error(&format!("1 {} true", op));
+        assert_type_error(&format!("1 {} false", op));
 
         // with Floats and Boolean
-        bjorn::interpret(&format!("1.0 {} true", op));
-        bjorn::interpret(&format!("1.0 {} false", op));
-        bjorn::interpret(&format!("true {} 1.0", op));
-        bjorn::interpret(&format!("false {} 1.0", op));
+        assert_type_error(&format!("1.0 {} true", op));
+        assert_type_error(&format!("1.0 {} false", op));
     }
 
+    // The right operand is checked only when evaluated
+    assert_type_error("false or 1");
+    assert_type_error("true and 1");
+    assert_type_error("false or 1.0");
+    assert_type_error("true and 1.0");
+
     // NOT
-    bjorn::interpret("not 1");
-    bjorn::interpret("not 1.0");
+    assert_type_error("not 1");
+    assert_type_error("not 1.0");
+
+}
 
+#[test]
+fn numeric_literals() {
+    assert_eq!(bjorn::interpret("0xFF + 0o10 + 0b11"), Ok(String::from("266")));
+    assert_eq!(bjorn::interpret("1_000 * 2"), Ok(String::from("2000")));
+    assert_eq!(bjorn::interpret("1e3 + .5"), Ok(String::from("1000.5")));
+    assert_eq!(bjorn::interpret("2147483647"), Ok(String::from("2147483647")));
+    assert_eq!(bjorn::interpret("-2147483647 - 1"), Ok(String::from("-2147483648")));
+    match bjorn::interpret("2147483648") {
+        Err(error @ BjornError::LexicalError(..)) => {
+            assert_eq!(error.help(), Some("integer literals must be at most 2147483647"));
+        },
+        other => panic!("expected a lexical error, got {:?}", other),
+    }
 }
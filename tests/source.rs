@@ -0,0 +1,65 @@
+extern crate bjorn;
+
+use bjorn::Span;
+
+/// Tokens of the input, without their location.
+fn tokens(input: &str) -> String {
+    let scan = bjorn::scan(input).unwrap();
+    format!("{:?}", scan.into_iter().map(|(token, _)| token).collect::<Vec<_>>())
+}
+
+fn spans(input: &str) -> Vec<Span> {
+    bjorn::scan(input).unwrap().into_iter().map(|(_, span)| span).collect()
+}
+
+const PROGRAM: &str = "def f(a):\n    if a:\n        return 1\n    return 2\n\nf(true)\n";
+
+#[test]
+fn crlf_line_endings() {
+    assert_eq!(tokens(&PROGRAM.replace("\n", "\r\n")), tokens(PROGRAM));
+    assert_eq!(bjorn::interpret(&PROGRAM.replace("\n", "\r\n")), Ok(String::from("1")));
+}
+
+#[test]
+fn cr_line_endings() {
+    assert_eq!(tokens(&PROGRAM.replace("\n", "\r")), tokens(PROGRAM));
+}
+
+#[test]
+fn mixed_line_endings() {
+    assert_eq!(tokens("a\r\nb\rc\n"), tokens("a\nb\nc\n"));
+}
+
+#[test]
+fn crlf_spans() {
+    // Spans are byte offsets in the original input
+    assert_eq!(spans("a\r\nb"), vec![
+        Span::new(0, 1, 1, 1),
+        Span::new(1, 3, 1, 2),
+        Span::new(3, 4, 2, 1),
+    ]);
+}
+
+#[test]
+fn byte_order_mark() {
+    assert_eq!(tokens(&format!("\u{feff}{}", PROGRAM)), tokens(PROGRAM));
+    assert_eq!(spans("\u{feff}a"), vec![Span::new(3, 4, 1, 1)]);
+}
+
+#[test]
+fn trailing_whitespace() {
+    assert_eq!(tokens("def f(a):   \n    return a \t\nf(1)  \n"), tokens("def f(a):\n    return a\nf(1)\n"));
+}
+
+#[test]
+fn whitespace_only_lines() {
+    assert_eq!(tokens("if a:\n    b\n  \n\t\n        \n    c\n"), tokens("if a:\n    b\n    c\n"));
+}
+
+#[test]
+fn missing_final_newline() {
+    assert_eq!(tokens("if a:\n    b"), tokens("if a:\n    b\n"));
+    assert_eq!(tokens("if a:\n    if b:\n        c"), tokens("if a:\n    if b:\n        c\n"));
+    assert_eq!(bjorn::interpret("def f():\n    return 1\nf()"), Ok(String::from("1")));
+    assert_eq!(bjorn::interpret("if true:\n    a = 1"), Ok(String::from("")));
+}
@@ -0,0 +1,54 @@
+extern crate bjorn;
+
+mod common;
+
+use bjorn::BjornError;
+use common::assert_type_error;
+
+#[test]
+fn string_literals() {
+    assert_eq!(bjorn::interpret("\"bjørn\""), Ok(String::from("bjørn")));
+    assert_eq!(bjorn::interpret("'a\\tb'"), Ok(String::from("a\tb")));
+    assert_eq!(bjorn::interpret("r'a\\tb'"), Ok(String::from("a\\tb")));
+    assert_eq!(bjorn::interpret("\"\"\"a\nb\"\"\""), Ok(String::from("a\nb")));
+    assert_eq!(bjorn::interpret("a = 'b'\na"), Ok(String::from("b")));
+}
+
+#[test]
+fn string_operations() {
+    assert_eq!(bjorn::interpret("'ab' + \"cd\""), Ok(String::from("abcd")));
+    assert_eq!(bjorn::interpret("'ab' * 3"), Ok(String::from("ababab")));
+    assert_eq!(bjorn::interpret("2 * 'ab'"), Ok(String::from("abab")));
+    assert_eq!(bjorn::interpret("'ab' * -1"), Ok(String::from("")));
+    assert_eq!(bjorn::interpret("'ab' * 0"), Ok(String::from("")));
+}
+
+#[test]
+fn string_comparisons() {
+    assert_eq!(bjorn::interpret("'a' == 'a'"), Ok(String::from("true")));
+    assert_eq!(bjorn::interpret("'a' != 'b'"), Ok(String::from("true")));
+    assert_eq!(bjorn::interpret("'a' < 'b'"), Ok(String::from("true")));
+    assert_eq!(bjorn::interpret("'ab' >= 'b'"), Ok(String::from("false")));
+}
+
+#[test]
+fn invalid_string_operations() {
+    assert_type_error("'a' + 1");
+    assert_type_error("1 + 'a'");
+    assert_type_error("'a' - 'a'");
+    assert_type_error("'a' * 'a'");
+    assert_type_error("'a' * 1.0");
+    assert_type_error("'a' / 1");
+    assert_type_error("-'a'");
+    assert_type_error("not 'a'");
+    assert_type_error("'1' == 1");
+    assert_type_error("'1' < 2");
+}
+
+#[test]
+fn invalid_string_literal() {
+    match bjorn::interpret("'abc") {
+        Err(BjornError::LexicalError(..)) => {},
+        other => panic!("expected a lexical error, got {:?}", other),
+    }
+}
@@ -0,0 +1,98 @@
+extern crate bjorn;
+
+mod common;
+
+use bjorn::BjornError;
+use common::{assert_type_error, assert_syntax_error, assert_arity_error};
+
+#[test]
+fn tuple_literals() {
+    assert_eq!(bjorn::interpret("()"), Ok(String::from("()")));
+    assert_eq!(bjorn::interpret("(1,)"), Ok(String::from("(1,)")));
+    assert_eq!(bjorn::interpret("(1)"), Ok(String::from("1")));
+    assert_eq!(bjorn::interpret("1, 'a', [2]"), Ok(String::from("(1, \"a\", [2])")));
+    assert_eq!(bjorn::interpret("t = 1, (2, 3),\nt"), Ok(String::from("(1, (2, 3))")));
+}
+
+#[test]
+fn tuple_operations() {
+    assert_eq!(bjorn::interpret("t = (1, 2, 3)\nt[-1]"), Ok(String::from("3")));
+    assert_eq!(bjorn::interpret("t = (1, 2, 3)\nt[1:]"), Ok(String::from("(2, 3)")));
+    assert_eq!(bjorn::interpret("(1, 2) + (3,)"), Ok(String::from("(1, 2, 3)")));
+    assert_eq!(bjorn::interpret("(1, 2) == (1.0, 2)"), Ok(String::from("true")));
+    assert_eq!(bjorn::interpret("(1, 2) < (1, 3)"), Ok(String::from("true")));
+    assert_eq!(bjorn::interpret("2 in (1, 2)"), Ok(String::from("true")));
+    assert_eq!(bjorn::interpret("len((1, 2))"), Ok(String::from("2")));
+    assert_eq!(bjorn::interpret("d = {(1, 'a'): 2}\nd[(1.0, 'a')]"), Ok(String::from("2")));
+    assert_type_error("t = (1, 2)\nt[0] = 3");
+    assert_type_error("(1,) + [2]");
+    assert_type_error("{([1],): 1}");
+}
+
+#[test]
+fn multiple_return_values() {
+    assert_eq!(
+        bjorn::interpret("def bounds(xs):\n    return xs[0], xs[-1]\nbounds([3, 1, 4])"),
+        Ok(String::from("(3, 4)"))
+    );
+    assert_eq!(
+        bjorn::interpret("def bounds(xs):\n    return xs[0], xs[-1]\nfirst, last = bounds('bjørn')\nlast + first"),
+        Ok(String::from("nb"))
+    );
+}
+
+#[test]
+fn builtin_divmod() {
+    assert_eq!(bjorn::interpret("divmod(17, 5)"), Ok(String::from("(3, 2)")));
+    assert_eq!(bjorn::interpret("divmod(-17, 5)"), Ok(String::from("(-4, 3)")));
+    assert_eq!(bjorn::interpret("q, r = divmod(7.5, 2)\nq * 10 + r"), Ok(String::from("31.5")));
+    assert_arity_error("divmod(1)");
+    assert_type_error("divmod('a', 2)");
+    match bjorn::interpret("divmod(1, 0)") {
+        Err(BjornError::RuntimeError(..)) => {},
+        other => panic!("expected a runtime error, got {:?}", other),
+    }
+}
+
+#[test]
+fn unpacking_assignment() {
+    assert_eq!(bjorn::interpret("a, b = 1, 2\na, b = b, a\n(a, b)"), Ok(String::from("(2, 1)")));
+    assert_eq!(bjorn::interpret("a, b = 'hé'\nb"), Ok(String::from("é")));
+    assert_eq!(bjorn::interpret("(a, b), c = [1, 2], 3\n(a, b, c)"), Ok(String::from("(1, 2, 3)")));
+    assert_eq!(bjorn::interpret("xs = [0, 0]\nxs[0], xs[1] = 1, 2\nxs"), Ok(String::from("[1, 2]")));
+    assert_eq!(bjorn::interpret("a, = [1]\na"), Ok(String::from("1")));
+}
+
+#[test]
+fn starred_assignment() {
+    assert_eq!(bjorn::interpret("first, *rest = [1, 2, 3]\n(first, rest)"), Ok(String::from("(1, [2, 3])")));
+    assert_eq!(bjorn::interpret("*init, last = range(0, 3)\n(init, last)"), Ok(String::from("([0, 1], 2)")));
+    assert_eq!(bjorn::interpret("a, *b, c = 1, 2\n(a, b, c)"), Ok(String::from("(1, [], 2)")));
+    assert_eq!(bjorn::interpret("*a, = 1, 2\na"), Ok(String::from("[1, 2]")));
+    assert_syntax_error("a, *b, *c = 1, 2, 3");
+    assert_syntax_error("*a = [1]");
+    assert_syntax_error("a = *b");
+}
+
+#[test]
+fn unpacking_arity_mismatch() {
+    assert_arity_error("a, b = 1, 2, 3");
+    assert_arity_error("a, b, c = 1, 2");
+    assert_arity_error("a, (b, c) = 1, (2,)");
+    assert_arity_error("a, *b, c = [1]");
+    assert_type_error("a, b = 1");
+}
+
+#[test]
+fn invalid_augmented_assignment() {
+    assert_syntax_error("a, b = 1, 2\na, b += 1");
+}
+
+#[test]
+fn for_statement_unpacking() {
+    assert_eq!(
+        bjorn::interpret("s = ''\nfor k, v in {'a': 1, 'b': 2}.items():\n    s += k * v\ns"),
+        Ok(String::from("abb"))
+    );
+    assert_arity_error("for a, b in [(1, 2, 3)]:\n    a");
+}
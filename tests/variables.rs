@@ -1,7 +1,64 @@
 extern crate bjorn;
 
+use bjorn::BjornError;
+
 #[test]
 fn assignment_statement() {
-    assert_eq!(bjorn::interpret("a = 1"), String::from(""));
-    assert_eq!(bjorn::interpret("a = 1\na"), String::from("1"));
+    assert_eq!(bjorn::interpret("a = 1"), Ok(String::from("")));
+    assert_eq!(bjorn::interpret("a = 1\na"), Ok(String::from("1")));
+}
+
+#[test]
+fn augmented_assignment_statement() {
+    assert_eq!(bjorn::interpret("i = 1\ni += 2\ni"), Ok(String::from("3")));
+    assert_eq!(bjorn::interpret("i = 1\ni -= 2\ni"), Ok(String::from("-1")));
+    assert_eq!(bjorn::interpret("i = 3\ni *= 2 + 1\ni"), Ok(String::from("9")));
+    assert_eq!(bjorn::interpret("i = 3\ni /= 2\ni"), Ok(String::from("1.5")));
+    assert_eq!(bjorn::interpret("i = 7\ni //= 2\ni"), Ok(String::from("3")));
+    assert_eq!(bjorn::interpret("i = 7\ni %= 4\ni"), Ok(String::from("3")));
+    assert_eq!(bjorn::interpret("i = 2\ni **= 3\ni"), Ok(String::from("8")));
+    assert_eq!(bjorn::interpret("s = \"a\"\ns += \"b\"\ns"), Ok(String::from("ab")));
+}
+
+#[test]
+fn invalid_augmented_assignment() {
+    match bjorn::interpret("i += 1") {
+        Err(BjornError::NameError(..)) => {},
+        other => panic!("expected a name error, got {:?}", other),
+    }
+    match bjorn::interpret("1 += 1") {
+        Err(BjornError::SyntaxError(..)) => {},
+        other => panic!("expected a syntax error, got {:?}", other),
+    }
+    match bjorn::interpret("i = 1\ni += true") {
+        Err(BjornError::TypeError(..)) => {},
+        other => panic!("expected a type error, got {:?}", other),
+    }
+}
+
+#[test]
+fn undefined_variable() {
+    match bjorn::interpret("a = 1\nb") {
+        Err(BjornError::NameError(..)) => {},
+        other => panic!("expected a name error, got {:?}", other),
+    }
+}
+
+#[test]
+fn undefined_variable_suggestion() {
+    let error = bjorn::interpret("counter = 1\ncountr").unwrap_err();
+    assert_eq!(error.help(), Some("did you mean `counter`?"));
+
+    let error = bjorn::interpret("def f():\n    return prnt\nf()").unwrap_err();
+    assert_eq!(error.help(), Some("did you mean `print`?"));
+
+    let error = bjorn::interpret("counter = 1\nxyz").unwrap_err();
+    assert_eq!(error.help(), None);
+}
+
+#[test]
+fn unicode_variables() {
+    assert_eq!(bjorn::interpret("π = 3\nørn = 2\nπ * ørn"), Ok(String::from("6")));
+    // Composed and decomposed forms of `é` name the same variable
+    assert_eq!(bjorn::interpret("caf\u{e9} = 1\ncafe\u{301}"), Ok(String::from("1")));
 }